@@ -234,11 +234,13 @@ mod tests {
     use group::ff::Field;
     use group::Curve;
     use rand::rngs::OsRng;
+    use rand::seq::IteratorRandom;
+    use rand::Rng;
 
     use crate::{
         aggregate_signature_shares, blind_message, sign_blinded_msg, unblind_signature, verify,
-        verify_blind_share, AggregatePublicKey, BlindedSignatureShare, BlindingKey, Message,
-        PublicKeyShare, SecretKeyShare,
+        verify_blind_share, AggregatePublicKey, BlindedMessage, BlindedSignatureShare, BlindingKey,
+        Message, PublicKeyShare, SecretKeyShare,
     };
 
     fn dealer_keygen(
@@ -299,6 +301,69 @@ mod tests {
         assert!(verify(msg, sig, pk));
     }
 
+    /// Signs a blinded message with all secret key shares and returns a random
+    /// subset of `n` shares keyed by peer index
+    fn random_signature_shares(
+        bmsg: BlindedMessage,
+        sks: &[SecretKeyShare],
+        n: usize,
+    ) -> BTreeMap<u64, BlindedSignatureShare> {
+        (1_u64..)
+            .zip(sks.iter().map(|sk| sign_blinded_msg(bmsg, *sk)))
+            .choose_multiple(&mut OsRng, n)
+            .into_iter()
+            .collect()
+    }
+
+    #[test]
+    fn test_aggregate_random_share_subsets() {
+        let (pk, _, sks) = dealer_keygen(5, 15);
+
+        for _ in 0..20 {
+            let msg = Message::from_bytes(&OsRng.gen::<[u8; 32]>());
+            let bkey = BlindingKey::random();
+            let bmsg = blind_message(msg, bkey);
+
+            // Any subset of at least threshold shares yields a valid signature …
+            let n = OsRng.gen_range(5..=15);
+            let bsig = aggregate_signature_shares(&random_signature_shares(bmsg, &sks, n));
+            assert!(verify(msg, unblind_signature(bkey, bsig), pk));
+
+            // … while fewer shares never do
+            let n = OsRng.gen_range(1..5);
+            let bsig = aggregate_signature_shares(&random_signature_shares(bmsg, &sks, n));
+            assert!(!verify(msg, unblind_signature(bkey, bsig), pk));
+        }
+    }
+
+    #[test]
+    fn test_aggregate_with_corrupted_share() {
+        let (pk, pks, sks) = dealer_keygen(5, 15);
+
+        for _ in 0..20 {
+            let msg = Message::from_bytes(&OsRng.gen::<[u8; 32]>());
+            let bkey = BlindingKey::random();
+            let bmsg = blind_message(msg, bkey);
+
+            let mut bsig_shares = random_signature_shares(bmsg, &sks, 5);
+
+            // Replace one share with a share of a different message
+            let corrupted_peer = *bsig_shares.keys().choose(&mut OsRng).unwrap();
+            let other_bmsg = blind_message(Message::from_bytes(b"Other"), BlindingKey::random());
+            let corrupted_share = sign_blinded_msg(other_bmsg, sks[corrupted_peer as usize - 1]);
+            bsig_shares.insert(corrupted_peer, corrupted_share);
+
+            assert!(!verify_blind_share(
+                bmsg,
+                corrupted_share,
+                pks[corrupted_peer as usize - 1]
+            ));
+
+            let bsig = aggregate_signature_shares(&bsig_shares);
+            assert!(!verify(msg, unblind_signature(bkey, bsig), pk));
+        }
+    }
+
     #[test]
     fn test_blindingkey_fingerprint_multiple_calls_same_result() {
         let bkey = BlindingKey::random();
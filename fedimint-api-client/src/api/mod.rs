@@ -994,6 +994,14 @@ pub struct FederationStatus {
     /// This should always be 0 if everything is okay, so a monitoring tool
     /// should generate an alert if this is not the case.
     pub peers_flagged: u64,
+    /// Set if the atomic broadcast has not made any progress in a while, which
+    /// should also generate an alert in a monitoring tool
+    #[serde(default)]
+    pub consensus_stalled: bool,
+    /// Number of transactions submitted to this peer that have not been
+    /// accepted by consensus yet
+    #[serde(default)]
+    pub pending_transactions: u64,
 }
 
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
@@ -322,6 +322,9 @@ enum AdminCmd {
     /// Download guardian config to back it up
     GuardianConfigBackup,
 
+    /// Export watch-only descriptors of the federation's on-chain funds
+    WatchOnlyDescriptors,
+
     Dkg(DkgAdminArgs),
 }
 
@@ -465,6 +468,10 @@ Examples:
     /// Gets the current fedimint AlephBFT block count
     SessionCount,
 
+    /// Fetches the outcome of a completed session, listing every accepted
+    /// consensus item together with the peer that contributed it
+    SessionOutcome { session_index: u64 },
+
     ConfigDecrypt {
         /// Encrypted config file
         #[arg(long = "in-file")]
@@ -783,6 +790,19 @@ impl FedimintCli {
                         .map_err_cli_msg("invalid response")?,
                 ))
             }
+            Command::Admin(AdminCmd::WatchOnlyDescriptors) => {
+                let client = self.client_open(&cli).await?;
+                let wallet = client.get_first_module::<WalletClientModule>();
+
+                let descriptors = cli
+                    .admin_client(client.get_config(), client.api_secret())?
+                    .with_module(wallet.id)
+                    .fetch_watch_only_descriptors(cli.auth()?)
+                    .await?;
+                Ok(CliOutput::Raw(
+                    serde_json::to_value(descriptors).map_err_cli_msg("invalid response")?,
+                ))
+            }
             Command::Admin(AdminCmd::Dkg(dkg_args)) => {
                 self.handle_admin_dkg_command(cli, dkg_args).await
             }
@@ -915,6 +935,31 @@ impl FedimintCli {
                 let count = client.api().session_count().await?;
                 Ok(CliOutput::EpochCount { count })
             }
+            Command::Dev(DevCmd::SessionOutcome { session_index }) => {
+                let client = self.client_open(&cli).await?;
+                let session_outcome = client
+                    .api()
+                    .await_block(session_index, client.decoders())
+                    .await
+                    .map_err_cli()?;
+
+                let items = session_outcome
+                    .items
+                    .iter()
+                    .map(|accepted_item| {
+                        json!({
+                            "peer": accepted_item.peer,
+                            "item": format!("{:?}", accepted_item.item),
+                        })
+                    })
+                    .collect::<Vec<_>>();
+
+                Ok(CliOutput::Raw(json!({
+                    "session_index": session_index,
+                    "header": hex::encode(session_outcome.header(session_index)),
+                    "items": items,
+                })))
+            }
             Command::Dev(DevCmd::ConfigDecrypt {
                 in_file,
                 out_file,
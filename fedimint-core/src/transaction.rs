@@ -146,6 +146,10 @@ pub enum TransactionError {
     Input(DynInputError),
     #[error("The transaction had an invalid output: {}", .0)]
     Output(DynOutputError),
+    /// Never part of a [`TransactionSubmissionOutcome`] since older clients
+    /// cannot decode it
+    #[error("The transaction is too large to be ordered: size={size}, limit={limit}")]
+    TooLarge { size: u64, limit: u64 },
 }
 
 #[derive(Debug, Encodable, Decodable, Clone, Eq, PartialEq)]
@@ -293,6 +293,16 @@ impl DatabaseDump {
                         "Aleph Units"
                     );
                 }
+                ConsensusRange::DbKeyPrefix::SubmittedTransaction => {
+                    push_db_pair_items_no_serde!(
+                        dbtx,
+                        ConsensusRange::SubmittedTransactionPrefix,
+                        ConsensusRange::SubmittedTransactionKey,
+                        ConsensusRange::SubmittedTransaction,
+                        consensus,
+                        "Submitted Transactions"
+                    );
+                }
                 // Module is a global prefix for all module data
                 ConsensusRange::DbKeyPrefix::Module => {}
             }
@@ -144,7 +144,7 @@ impl FedimintDBTool {
     }
 
     pub fn with_default_modules_inits(self) -> Self {
-        self.with_server_module_init(WalletInit)
+        self.with_server_module_init(WalletInit::default())
             .with_server_module_init(MintInit)
             .with_server_module_init(LightningInit)
             .with_server_module_init(fedimint_lnv2_server::LightningInit)
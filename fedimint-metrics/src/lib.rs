@@ -2,6 +2,7 @@
 #![allow(clippy::missing_errors_doc)]
 
 use std::net::SocketAddr;
+use std::sync::atomic::{AtomicBool, Ordering};
 
 use axum::http::StatusCode;
 use axum::routing::get;
@@ -50,11 +51,36 @@ async fn get_metrics() -> (StatusCode, String) {
     }
 }
 
+/// Whether the server is ready to serve requests, reported by `/health/ready`.
+/// This only covers the server's own api, the state of consensus is reported
+/// by the status endpoint and the consensus metrics instead.
+static READY: AtomicBool = AtomicBool::new(false);
+
+/// Marks the server as ready or not ready for the readiness probe
+pub fn set_ready(ready: bool) {
+    READY.store(ready, Ordering::Relaxed);
+}
+
+async fn get_health_live() -> (StatusCode, &'static str) {
+    (StatusCode::OK, "live")
+}
+
+async fn get_health_ready() -> (StatusCode, &'static str) {
+    if READY.load(Ordering::Relaxed) {
+        (StatusCode::OK, "ready")
+    } else {
+        (StatusCode::SERVICE_UNAVAILABLE, "not ready")
+    }
+}
+
 pub async fn run_api_server(
     bind_address: SocketAddr,
     task_group: TaskGroup,
 ) -> anyhow::Result<TaskShutdownToken> {
-    let app = Router::new().route("/metrics", get(get_metrics));
+    let app = Router::new()
+        .route("/metrics", get(get_metrics))
+        .route("/health/live", get(get_health_live))
+        .route("/health/ready", get(get_health_ready));
     let listener = TcpListener::bind(bind_address).await?;
     let serve = axum::serve(listener, app.into_make_service());
 
@@ -5,10 +5,11 @@ use std::path::Path;
 
 use fedimint_aead::{encrypted_read, encrypted_write, get_encryption_key, LessSafeKey};
 use fedimint_core::config::ServerModuleInitRegistry;
+use fedimint_core::module::ApiAuth;
 use serde::de::DeserializeOwned;
 use serde::Serialize;
 
-use crate::config::ServerConfig;
+use crate::config::{ServerConfig, ServerConfigPrivate};
 
 /// Client configuration file
 pub const CLIENT_CONFIG: &str = "client";
@@ -32,6 +33,10 @@ pub const SALT_FILE: &str = "private.salt";
 /// send a password in via the API
 pub const PLAINTEXT_PASSWORD: &str = "password.private";
 
+/// New plain-text password written while changing the password, renamed to
+/// [`PLAINTEXT_PASSWORD`] once the private cfg file was re-encrypted
+pub const NEW_PLAINTEXT_PASSWORD: &str = "password-new.private";
+
 /// Database file name
 pub const DB_FILE: &str = "database";
 
@@ -44,6 +49,10 @@ pub const ENCRYPTED_EXT: &str = "encrypt";
 /// directory and the staging directory is removed
 pub const CONFIG_STAGING_DIR: &str = "cfg_staging";
 
+/// Private config re-encrypted with a new password, renamed to
+/// [`PRIVATE_CONFIG`] once it was written completely
+const NEW_PRIVATE_CONFIG: &str = "private-new";
+
 /// Reads the server from the local, private, and consensus cfg files
 pub fn read_server_config(password: &str, path: &Path) -> anyhow::Result<ServerConfig> {
     let salt = fs::read_to_string(path.join(SALT_FILE))?;
@@ -56,6 +65,72 @@ pub fn read_server_config(password: &str, path: &Path) -> anyhow::Result<ServerC
     })
 }
 
+/// Reads the server config with the password stored in
+/// [`PLAINTEXT_PASSWORD`], returns `None` if there is no such file.
+///
+/// If a password change was interrupted after the private cfg file was
+/// replaced but before the new password was moved into place, only the staged
+/// [`NEW_PLAINTEXT_PASSWORD`] decrypts the config, so we finish the password
+/// change with it.
+pub fn read_server_config_with_plaintext_password(
+    path: &Path,
+) -> anyhow::Result<Option<ServerConfig>> {
+    let Ok(password) = fs::read_to_string(path.join(PLAINTEXT_PASSWORD)) else {
+        return Ok(None);
+    };
+
+    let new_password_path = path.join(NEW_PLAINTEXT_PASSWORD);
+
+    match read_server_config(&password, path) {
+        Ok(cfg) => {
+            // The password change was interrupted before the private cfg file was
+            // replaced, so the staged password was never used
+            if new_password_path.exists() {
+                fs::remove_file(new_password_path)?;
+            }
+
+            Ok(Some(cfg))
+        }
+        Err(error) => {
+            let Ok(new_password) = fs::read_to_string(&new_password_path) else {
+                return Err(error);
+            };
+
+            let cfg = read_server_config(&new_password, path)?;
+
+            fs::rename(new_password_path, path.join(PLAINTEXT_PASSWORD))?;
+
+            Ok(Some(cfg))
+        }
+    }
+}
+
+/// Re-encrypts the private cfg file with a new password, which also becomes
+/// the password for the admin API. The salt is kept, so the private cfg file is
+/// the only file that changes and it is replaced atomically.
+pub fn change_password(path: &Path, password: &str, new_password: &str) -> anyhow::Result<()> {
+    let salt = fs::read_to_string(path.join(SALT_FILE))?;
+    let key = get_encryption_key(password, &salt)?;
+    let new_key = get_encryption_key(new_password, &salt)?;
+
+    let mut private: ServerConfigPrivate = encrypted_json_read(&key, &path.join(PRIVATE_CONFIG))?;
+    private.api_auth = ApiAuth(new_password.to_string());
+
+    // Remove leftovers of a previous attempt that failed before the rename
+    let new_private_path = path.join(NEW_PRIVATE_CONFIG);
+    if new_private_path.with_extension(ENCRYPTED_EXT).exists() {
+        fs::remove_file(new_private_path.with_extension(ENCRYPTED_EXT))?;
+    }
+
+    encrypted_json_write(&private, &new_key, &new_private_path)?;
+    fs::rename(
+        new_private_path.with_extension(ENCRYPTED_EXT),
+        path.join(PRIVATE_CONFIG).with_extension(ENCRYPTED_EXT),
+    )?;
+
+    Ok(())
+}
+
 /// Reads a plaintext json file into a struct
 fn plaintext_json_read<T: Serialize + DeserializeOwned>(path: &Path) -> anyhow::Result<T> {
     let string = fs::read_to_string(path.with_extension(JSON_EXT))?;
@@ -126,3 +201,93 @@ fn encrypted_json_write<T: Serialize + DeserializeOwned>(
     let bytes = serde_json::to_string(obj)?.into_bytes();
     encrypted_write(bytes, key, path.with_extension(ENCRYPTED_EXT))
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+    use std::fs;
+
+    use fedimint_aead::{get_encryption_key, random_salt};
+    use fedimint_core::config::ServerModuleInitRegistry;
+    use fedimint_core::module::ApiAuth;
+    use fedimint_core::secp256k1;
+    use rand::rngs::OsRng;
+
+    use super::{
+        change_password, encrypted_json_read, encrypted_json_write,
+        read_server_config_with_plaintext_password, write_server_config, NEW_PLAINTEXT_PASSWORD,
+        PLAINTEXT_PASSWORD, PRIVATE_CONFIG, SALT_FILE,
+    };
+    use crate::config::tests::single_guardian_config;
+    use crate::config::{gen_cert_and_key, ServerConfigPrivate};
+
+    #[test]
+    fn change_password_reencrypts_private_config() {
+        let dir = tempfile::tempdir().unwrap();
+        let salt = random_salt();
+        fs::write(dir.path().join(SALT_FILE), &salt).unwrap();
+
+        let private = ServerConfigPrivate {
+            api_auth: ApiAuth("old-password".to_string()),
+            tls_key: gen_cert_and_key("peer-0").unwrap().1,
+            broadcast_secret_key: secp256k1::generate_keypair(&mut OsRng).0,
+            modules: BTreeMap::new(),
+        };
+
+        let old_key = get_encryption_key("old-password", &salt).unwrap();
+        encrypted_json_write(&private, &old_key, &dir.path().join(PRIVATE_CONFIG)).unwrap();
+
+        change_password(dir.path(), "old-password", "new-password").unwrap();
+
+        let new_key = get_encryption_key("new-password", &salt).unwrap();
+        let changed: ServerConfigPrivate =
+            encrypted_json_read(&new_key, &dir.path().join(PRIVATE_CONFIG)).unwrap();
+
+        assert_eq!(changed.api_auth, ApiAuth("new-password".to_string()));
+        assert_eq!(changed.tls_key, private.tls_key);
+        assert_eq!(changed.broadcast_secret_key, private.broadcast_secret_key);
+
+        assert!(encrypted_json_read::<ServerConfigPrivate>(
+            &old_key,
+            &dir.path().join(PRIVATE_CONFIG)
+        )
+        .is_err());
+        assert!(change_password(dir.path(), "old-password", "other-password").is_err());
+    }
+
+    #[test]
+    fn interrupted_password_change_is_completed_on_startup() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join(SALT_FILE), random_salt()).unwrap();
+
+        let cfg = single_guardian_config();
+        write_server_config(
+            &cfg,
+            dir.path(),
+            "old-password",
+            &ServerModuleInitRegistry::default(),
+            None,
+        )
+        .unwrap();
+
+        // We crash after re-encrypting the private config but before moving the
+        // staged password into place
+        fs::write(dir.path().join(PLAINTEXT_PASSWORD), "old-password").unwrap();
+        fs::write(dir.path().join(NEW_PLAINTEXT_PASSWORD), "new-password").unwrap();
+        change_password(dir.path(), "old-password", "new-password").unwrap();
+
+        let recovered = read_server_config_with_plaintext_password(dir.path())
+            .unwrap()
+            .expect("Password file exists");
+
+        assert_eq!(
+            recovered.private.api_auth,
+            ApiAuth("new-password".to_string())
+        );
+        assert_eq!(
+            fs::read_to_string(dir.path().join(PLAINTEXT_PASSWORD)).unwrap(),
+            "new-password"
+        );
+        assert!(!dir.path().join(NEW_PLAINTEXT_PASSWORD).exists());
+    }
+}
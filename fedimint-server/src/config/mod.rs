@@ -4,6 +4,7 @@ use std::net::SocketAddr;
 use std::time::Duration;
 
 use anyhow::{bail, format_err};
+use bitcoin_hashes::sha256;
 use fedimint_core::admin_client::ConfigGenParamsConsensus;
 pub use fedimint_core::config::{
     serde_binary_human_readable, ClientConfig, DkgError, DkgPeerMsg, DkgResult, FederationId,
@@ -11,6 +12,7 @@ pub use fedimint_core::config::{
     ServerModuleConsensusConfig, ServerModuleInitRegistry, TypedServerModuleConfig,
 };
 use fedimint_core::core::{ModuleInstanceId, ModuleKind, MODULE_INSTANCE_ID_GLOBAL};
+use fedimint_core::endpoint_constants::SUBMIT_TRANSACTION_ENDPOINT;
 use fedimint_core::envs::is_running_in_test_env;
 use fedimint_core::invite_code::InviteCode;
 use fedimint_core::module::{
@@ -31,10 +33,11 @@ use tracing::{error, info};
 
 use crate::config::api::ConfigGenParamsLocal;
 use crate::config::distributedgen::{DkgRunner, PeerHandleOps};
-use crate::envs::FM_MAX_CLIENT_CONNECTIONS_ENV;
+use crate::envs::{FM_MAX_CLIENT_CONNECTIONS_ENV, FM_SUBMIT_TRANSACTION_RATE_LIMIT_ENV};
 use crate::fedimint_core::encoding::Encodable;
 use crate::fedimint_core::NumPeersExt;
 use crate::multiplexed::PeerConnectionMultiplexer;
+use crate::net::api::rate_limit::RateLimits;
 use crate::net::connect::{dns_sanitize, Connector, TlsConfig};
 use crate::net::peers::{DelayCalculator, NetworkConfig};
 use crate::net::peers_reliable::ReconnectPeerConnectionsReliable;
@@ -47,6 +50,8 @@ pub mod io;
 /// The default maximum open connections the API can handle
 const DEFAULT_MAX_CLIENT_CONNECTIONS: u32 = 1000;
 
+const DEFAULT_SUBMIT_TRANSACTION_RATE_LIMIT: u32 = 100;
+
 /// Consensus broadcast settings that result in 3 minutes session time
 const DEFAULT_BROADCAST_ROUND_DELAY_MS: u16 = 50;
 const DEFAULT_BROADCAST_ROUNDS_PER_SESSION: u16 = 3600;
@@ -167,6 +172,48 @@ pub struct ConfigGenParams {
 }
 
 impl ServerConfigConsensus {
+    /// Consensus hashes of the individual sections of the config, used to
+    /// diagnose which sections differ if the config doesn't match a peer's
+    pub fn section_consensus_hashes(&self) -> BTreeMap<String, sha256::Hash> {
+        // We destructure the config so that adding a field fails to compile until
+        // it is covered here as well
+        let Self {
+            code_version,
+            version,
+            broadcast_public_keys,
+            broadcast_rounds_per_session,
+            api_endpoints,
+            tls_certs,
+            modules,
+            meta,
+        } = self;
+
+        let mut hashes = BTreeMap::from([
+            ("code_version".to_string(), code_version.consensus_hash()),
+            ("version".to_string(), version.consensus_hash()),
+            (
+                "broadcast_public_keys".to_string(),
+                broadcast_public_keys.consensus_hash(),
+            ),
+            (
+                "broadcast_rounds_per_session".to_string(),
+                broadcast_rounds_per_session.consensus_hash(),
+            ),
+            ("api_endpoints".to_string(), api_endpoints.consensus_hash()),
+            ("tls_certs".to_string(), tls_certs.consensus_hash()),
+            ("meta".to_string(), meta.consensus_hash()),
+        ]);
+
+        for (module_instance_id, module) in modules {
+            hashes.insert(
+                format!("modules.{module_instance_id}"),
+                module.consensus_hash(),
+            );
+        }
+
+        hashes
+    }
+
     pub fn iter_module_instances(
         &self,
     ) -> impl Iterator<Item = (ModuleInstanceId, &ModuleKind)> + '_ {
@@ -203,7 +250,7 @@ impl ServerConfig {
     pub fn supported_api_versions() -> SupportedCoreApiVersions {
         SupportedCoreApiVersions {
             core_consensus: CORE_CONSENSUS_VERSION,
-            api: MultiApiVersion::try_from_iter([ApiVersion { major: 0, minor: 2 }])
+            api: MultiApiVersion::try_from_iter([ApiVersion { major: 0, minor: 3 }])
                 .expect("not version conflicts"),
         }
     }
@@ -691,6 +738,19 @@ pub fn max_connections() -> u32 {
         .unwrap_or(DEFAULT_MAX_CLIENT_CONNECTIONS)
 }
 
+/// Per connection limits for the requests per second of expensive API methods
+pub fn api_rate_limits() -> RateLimits {
+    let submit_transaction_rate_limit = env::var(FM_SUBMIT_TRANSACTION_RATE_LIMIT_ENV)
+        .ok()
+        .and_then(|s| s.parse().ok())
+        .unwrap_or(DEFAULT_SUBMIT_TRANSACTION_RATE_LIMIT);
+
+    RateLimits::from([(
+        SUBMIT_TRANSACTION_ENDPOINT.to_owned(),
+        submit_transaction_rate_limit,
+    )])
+}
+
 pub async fn connect<T>(
     network: NetworkConfig,
     certs: TlsConfig,
@@ -796,3 +856,84 @@ mod serde_tls_key {
         Ok(rustls::PrivateKey(bytes))
     }
 }
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use std::collections::{BTreeMap, HashMap};
+
+    use fedimint_core::admin_client::{ConfigGenParamsConsensus, PeerServerParams};
+    use fedimint_core::config::{ServerModuleConfigGenParamsRegistry, ServerModuleInitRegistry};
+    use fedimint_core::module::{ApiAuth, CORE_CONSENSUS_VERSION};
+    use fedimint_core::PeerId;
+
+    use super::{gen_cert_and_key, ConfigGenParams, ServerConfig, ServerConfigConsensus};
+    use crate::config::api::ConfigGenParamsLocal;
+
+    /// Builds the config of a federation with a single guardian and no modules
+    pub(crate) fn single_guardian_config() -> ServerConfig {
+        let peer = PeerId::from(0);
+        let (cert, key) = gen_cert_and_key("peer-0").unwrap();
+
+        let params = ConfigGenParams {
+            local: ConfigGenParamsLocal {
+                our_id: peer,
+                our_private_key: key,
+                api_auth: ApiAuth("pass".to_string()),
+                p2p_bind: "127.0.0.1:10000".parse().unwrap(),
+                api_bind: "127.0.0.1:10001".parse().unwrap(),
+                max_connections: 10,
+            },
+            consensus: ConfigGenParamsConsensus {
+                peers: BTreeMap::from([(
+                    peer,
+                    PeerServerParams {
+                        cert,
+                        p2p_url: "fedimint://127.0.0.1:10000".parse().unwrap(),
+                        api_url: "ws://127.0.0.1:10001".parse().unwrap(),
+                        name: "peer-0".to_string(),
+                        status: None,
+                    },
+                )]),
+                meta: BTreeMap::new(),
+                modules: ServerModuleConfigGenParamsRegistry::default(),
+            },
+        };
+
+        ServerConfig::trusted_dealer_gen(
+            &HashMap::from([(peer, params)]),
+            &ServerModuleInitRegistry::default(),
+            "test",
+        )
+        .remove(&peer)
+        .unwrap()
+    }
+
+    #[test]
+    fn section_consensus_hashes_only_report_changed_section() {
+        let consensus = ServerConfigConsensus {
+            code_version: "0.4.0-alpha".to_string(),
+            version: CORE_CONSENSUS_VERSION,
+            broadcast_public_keys: BTreeMap::new(),
+            broadcast_rounds_per_session: 3600,
+            api_endpoints: BTreeMap::new(),
+            tls_certs: BTreeMap::new(),
+            modules: BTreeMap::new(),
+            meta: BTreeMap::new(),
+        };
+
+        let mut changed = consensus.clone();
+        changed
+            .meta
+            .insert("federation_name".to_string(), "\"fedimint\"".to_string());
+
+        let hashes = consensus.section_consensus_hashes();
+        let changed_hashes = changed.section_consensus_hashes();
+
+        let differing_sections = hashes
+            .keys()
+            .filter(|section| hashes[*section] != changed_hashes[*section])
+            .collect::<Vec<_>>();
+
+        assert_eq!(differing_sections, vec!["meta"]);
+    }
+}
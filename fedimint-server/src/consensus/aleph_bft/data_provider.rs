@@ -1,14 +1,20 @@
 use std::collections::BTreeSet;
 
+use bitcoin_hashes::sha256;
 use fedimint_core::config::ALEPH_BFT_UNIT_BYTE_LIMIT;
 use fedimint_core::encoding::Encodable;
 use fedimint_core::epoch::ConsensusItem;
 use fedimint_core::session_outcome::SchnorrSignature;
-use fedimint_core::TransactionId;
 use tokio::sync::watch;
 
 use crate::LOG_CONSENSUS;
 
+// the length of a vector is encoded in at most 9 bytes
+const BATCH_LENGTH_BYTES: usize = 9;
+
+/// The maximum encoded size of a consensus item that still fits into a unit
+pub const MAX_CONSENSUS_ITEM_BYTES: usize = ALEPH_BFT_UNIT_BYTE_LIMIT - BATCH_LENGTH_BYTES;
+
 #[derive(
     Clone, Debug, PartialEq, Eq, Hash, parity_scale_codec::Encode, parity_scale_codec::Decode,
 )]
@@ -31,7 +37,10 @@ impl UnitData {
 pub struct DataProvider {
     mempool_item_receiver: async_channel::Receiver<ConsensusItem>,
     signature_receiver: watch::Receiver<Option<SchnorrSignature>>,
-    submitted_transactions: BTreeSet<TransactionId>,
+    /// Consensus hashes of all items we have already included in a unit during
+    /// this session. Every unit we create is eventually ordered, so proposing
+    /// the same item twice would only bloat the session.
+    submitted_items: BTreeSet<sha256::Hash>,
     leftover_item: Option<ConsensusItem>,
 }
 
@@ -43,7 +52,7 @@ impl DataProvider {
         Self {
             mempool_item_receiver,
             signature_receiver,
-            submitted_transactions: BTreeSet::new(),
+            submitted_items: BTreeSet::new(),
             leftover_item: None,
         }
     }
@@ -57,8 +66,7 @@ impl aleph_bft::DataProvider<UnitData> for DataProvider {
             return Some(UnitData::Signature(signature));
         }
 
-        // the length of a vector is encoded in at most 9 bytes
-        let mut n_bytes = 9;
+        let mut n_bytes = BATCH_LENGTH_BYTES;
         let mut items = Vec::new();
 
         if let Some(item) = self.leftover_item.take() {
@@ -75,10 +83,8 @@ impl aleph_bft::DataProvider<UnitData> for DataProvider {
         // if the channel is empty we want to return the batch immediately in order to
         // not delay the creation of our next unit, even if the batch is empty
         while let Ok(item) = self.mempool_item_receiver.try_recv() {
-            if let ConsensusItem::Transaction(transaction) = &item {
-                if !self.submitted_transactions.insert(transaction.tx_hash()) {
-                    continue;
-                }
+            if !self.submitted_items.insert(item.consensus_hash()) {
+                continue;
             }
 
             let n_bytes_item = item.consensus_encode_to_vec().len();
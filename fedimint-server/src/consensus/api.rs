@@ -2,6 +2,7 @@
 use std::cmp::Ordering;
 use std::collections::{BTreeMap, HashMap};
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{self, AtomicBool};
 use std::sync::Arc;
 
 use anyhow::{anyhow, Result};
@@ -24,8 +25,9 @@ use fedimint_core::endpoint_constants::{
     AWAIT_SIGNED_SESSION_OUTCOME_ENDPOINT, AWAIT_TRANSACTION_ENDPOINT, BACKUP_ENDPOINT,
     CLIENT_CONFIG_ENDPOINT, CLIENT_CONFIG_JSON_ENDPOINT, FEDERATION_ID_ENDPOINT,
     GUARDIAN_CONFIG_BACKUP_ENDPOINT, INVITE_CODE_ENDPOINT, RECOVER_ENDPOINT,
-    SERVER_CONFIG_CONSENSUS_HASH_ENDPOINT, SESSION_COUNT_ENDPOINT, SESSION_STATUS_ENDPOINT,
-    SHUTDOWN_ENDPOINT, STATUS_ENDPOINT, SUBMIT_TRANSACTION_ENDPOINT, VERSION_ENDPOINT,
+    SERVER_CONFIG_CONSENSUS_HASH_ENDPOINT, SERVER_CONFIG_CONSENSUS_SECTION_HASHES_ENDPOINT,
+    SESSION_COUNT_ENDPOINT, SESSION_STATUS_ENDPOINT, SHUTDOWN_ENDPOINT, STATUS_ENDPOINT,
+    SUBMIT_TRANSACTION_ENDPOINT, VERSION_ENDPOINT,
 };
 use fedimint_core::epoch::ConsensusItem;
 use fedimint_core::module::audit::{Audit, AuditSummary};
@@ -43,18 +45,26 @@ use fedimint_core::transaction::{
 use fedimint_core::{OutPoint, PeerId, TransactionId};
 use fedimint_logging::LOG_NET_API;
 use futures::StreamExt;
-use tokio::sync::{watch, RwLock};
-use tracing::{debug, info};
+use tokio::sync::{broadcast, watch, RwLock};
+use tracing::{debug, info, instrument, warn};
 
 use crate::config::io::{
     CONSENSUS_CONFIG, ENCRYPTED_EXT, JSON_EXT, LOCAL_CONFIG, PRIVATE_CONFIG, SALT_FILE,
 };
 use crate::config::ServerConfig;
-use crate::consensus::db::{AcceptedItemPrefix, AcceptedTransactionKey, SignedSessionOutcomeKey};
+use crate::consensus::aleph_bft::data_provider::MAX_CONSENSUS_ITEM_BYTES;
+use crate::consensus::db::{
+    AcceptedItemPrefix, AcceptedTransactionKey, SignedSessionOutcomeKey, SubmittedTransaction,
+    SubmittedTransactionKey, SubmittedTransactionPrefix,
+};
 use crate::consensus::engine::get_finished_session_count_static;
+use crate::consensus::event::ConsensusEvent;
 use crate::consensus::transaction::process_transaction_with_dbtx;
 use crate::fedimint_core::encoding::Encodable;
-use crate::metrics::{BACKUP_WRITE_SIZE_BYTES, STORED_BACKUPS_COUNT};
+use crate::metrics::{
+    transaction_rejection_reason, BACKUP_WRITE_SIZE_BYTES,
+    CONSENSUS_SUBMITTED_TRANSACTIONS_REJECTED_TOTAL, STORED_BACKUPS_COUNT,
+};
 use crate::net::api::{check_auth, ApiResult, HasApiContext};
 
 #[derive(Clone)]
@@ -74,6 +84,8 @@ pub struct ConsensusApi {
     pub shutdown_sender: watch::Sender<Option<u64>>,
     pub connection_status_channels: Arc<RwLock<BTreeMap<PeerId, PeerConnectionStatus>>>,
     pub last_ci_by_peer: Arc<RwLock<BTreeMap<PeerId, u64>>>,
+    pub consensus_stalled: Arc<AtomicBool>,
+    pub event_sender: broadcast::Sender<ConsensusEvent>,
     pub supported_api_versions: SupportedApiVersionsSummary,
 }
 
@@ -82,6 +94,11 @@ impl ConsensusApi {
         &self.supported_api_versions
     }
 
+    /// Subscribes to the events emitted by consensus from now on
+    pub fn subscribe_consensus_events(&self) -> broadcast::Receiver<ConsensusEvent> {
+        self.event_sender.subscribe()
+    }
+
     pub fn get_active_api_secret(&self) -> Option<String> {
         // TODO: In the future, we might want to fetch it from the DB, so it's possible
         // to customize from the UX
@@ -90,14 +107,48 @@ impl ConsensusApi {
 
     // we want to return an error if and only if the submitted transaction is
     // invalid and will be rejected if we were to submit it to consensus
+    #[instrument(
+        target = "fm::net::api",
+        name = "submit_transaction",
+        skip_all,
+        fields(txid = %transaction.tx_hash()),
+        level = "info"
+    )]
     pub async fn submit_transaction(
         &self,
         transaction: Transaction,
+    ) -> Result<TransactionId, TransactionError> {
+        let result = self.validate_and_submit_transaction(transaction).await;
+
+        if let Err(error) = &result {
+            CONSENSUS_SUBMITTED_TRANSACTIONS_REJECTED_TOTAL
+                .with_label_values(&[transaction_rejection_reason(error)])
+                .inc();
+        }
+
+        result
+    }
+
+    async fn validate_and_submit_transaction(
+        &self,
+        transaction: Transaction,
     ) -> Result<TransactionId, TransactionError> {
         let txid = transaction.tx_hash();
 
         debug!(target: LOG_NET_API, %txid, "Received a submitted transaction");
 
+        // A transaction that does not fit into a single unit can never be ordered
+        let size = ConsensusItem::Transaction(transaction.clone())
+            .consensus_encode_to_vec()
+            .len();
+
+        if MAX_CONSENSUS_ITEM_BYTES < size {
+            return Err(TransactionError::TooLarge {
+                size: size as u64,
+                limit: MAX_CONSENSUS_ITEM_BYTES as u64,
+            });
+        }
+
         // Create read-only DB tx so that the read state is consistent
         let mut dbtx = self.db.begin_transaction_nc().await;
         // we already processed the transaction before
@@ -115,11 +166,33 @@ impl ConsensusApi {
 
         process_transaction_with_dbtx(self.modules.clone(), &mut dbtx, &transaction).await?;
 
+        // We persist the transaction until it is accepted so that it is resubmitted
+        // if we restart before it was ordered by the atomic broadcast
+        let mut submission_dbtx = self.db.begin_transaction().await;
+
+        submission_dbtx
+            .insert_entry(
+                &SubmittedTransactionKey(txid),
+                &SubmittedTransaction {
+                    transaction: transaction.clone(),
+                    submitted_at: fedimint_core::time::now(),
+                },
+            )
+            .await;
+
+        if let Err(e) = submission_dbtx.commit_tx_result().await {
+            warn!(target: LOG_NET_API, %txid, %e, "Failed to persist submitted transaction");
+        }
+
         self.submission_sender
             .send(ConsensusItem::Transaction(transaction))
             .await
             .ok();
 
+        self.event_sender
+            .send(ConsensusEvent::TransactionSubmitted(txid))
+            .ok();
+
         Ok(txid)
     }
 
@@ -225,12 +298,37 @@ impl ConsensusApi {
             .filter(|status| status.connection_status == PeerConnectionStatus::Disconnected)
             .count() as u64;
 
+        let mut dbtx = self.db.begin_transaction_nc().await;
+
+        let submitted_txids = dbtx
+            .find_by_prefix(&SubmittedTransactionPrefix)
+            .await
+            .map(|(key, _)| key.0)
+            .collect::<Vec<_>>()
+            .await;
+
+        // Accepted transactions are only removed periodically by the resubmission
+        // task, so we have to skip them here
+        let mut pending_transactions = 0;
+
+        for txid in submitted_txids {
+            if dbtx
+                .get_value(&AcceptedTransactionKey(txid))
+                .await
+                .is_none()
+            {
+                pending_transactions += 1;
+            }
+        }
+
         Ok(FederationStatus {
             session_count,
             status_by_peer,
             peers_online,
             peers_offline,
             peers_flagged,
+            consensus_stalled: self.consensus_stalled.load(atomic::Ordering::Relaxed),
+            pending_transactions,
         })
     }
 
@@ -422,9 +520,18 @@ pub fn server_endpoints() -> Vec<ApiEndpoint<ConsensusApi>> {
                     .try_into_inner(&fedimint.modules.decoder_registry())
                     .map_err(|e| ApiError::bad_request(e.to_string()))?;
 
+                let outcome = match fedimint.submit_transaction(transaction).await {
+                    // Clients that predate this variant cannot decode it, so we reject the
+                    // request instead of returning it as part of the outcome
+                    Err(error @ TransactionError::TooLarge { .. }) => {
+                        return Err(ApiError::bad_request(error.to_string()));
+                    }
+                    outcome => outcome,
+                };
+
                 // we return an inner error if and only if the submitted transaction is
                 // invalid and will be rejected if we were to submit it to consensus
-                Ok((&TransactionSubmissionOutcome(fedimint.submit_transaction(transaction).await)).into())
+                Ok((&TransactionSubmissionOutcome(outcome)).into())
             }
         },
         api_endpoint! {
@@ -488,6 +595,13 @@ pub fn server_endpoints() -> Vec<ApiEndpoint<ConsensusApi>> {
                 Ok(fedimint.cfg.consensus.consensus_hash())
             }
         },
+        api_endpoint! {
+            SERVER_CONFIG_CONSENSUS_SECTION_HASHES_ENDPOINT,
+            ApiVersion::new(0, 3),
+            async |fedimint: &ConsensusApi, _context, _v: ()| -> BTreeMap<String, sha256::Hash> {
+                Ok(fedimint.cfg.consensus.section_consensus_hashes())
+            }
+        },
         api_endpoint! {
             STATUS_ENDPOINT,
             ApiVersion::new(0, 0),
@@ -1,10 +1,12 @@
 use std::collections::BTreeMap;
 use std::fmt::Debug;
+use std::time::SystemTime;
 
 use fedimint_core::core::ModuleInstanceId;
 use fedimint_core::db::{DatabaseVersion, ServerMigrationFn, MODULE_GLOBAL_PREFIX};
 use fedimint_core::encoding::{Decodable, Encodable};
 use fedimint_core::session_outcome::{AcceptedItem, SignedSessionOutcome};
+use fedimint_core::transaction::Transaction;
 use fedimint_core::{impl_db_lookup, impl_db_record, TransactionId};
 use serde::Serialize;
 use strum_macros::EnumIter;
@@ -18,6 +20,7 @@ pub enum DbKeyPrefix {
     AcceptedTransaction = 0x02,
     SignedSessionOutcome = 0x04,
     AlephUnits = 0x05,
+    SubmittedTransaction = 0x06,
     Module = MODULE_GLOBAL_PREFIX,
 }
 
@@ -89,6 +92,31 @@ impl_db_record!(
 );
 impl_db_lookup!(key = AlephUnitsKey, query_prefix = AlephUnitsPrefix);
 
+/// Transactions submitted through our API that have not been accepted by
+/// consensus yet, kept so they can be resubmitted after a restart
+#[derive(Debug, Encodable, Decodable)]
+pub struct SubmittedTransactionKey(pub TransactionId);
+
+#[derive(Debug, Clone, Encodable, Decodable)]
+pub struct SubmittedTransaction {
+    pub transaction: Transaction,
+    pub submitted_at: SystemTime,
+}
+
+#[derive(Debug, Encodable, Decodable)]
+pub struct SubmittedTransactionPrefix;
+
+impl_db_record!(
+    key = SubmittedTransactionKey,
+    value = SubmittedTransaction,
+    db_prefix = DbKeyPrefix::SubmittedTransaction,
+    notify_on_modify = false,
+);
+impl_db_lookup!(
+    key = SubmittedTransactionKey,
+    query_prefix = SubmittedTransactionPrefix
+);
+
 pub fn get_global_database_migrations() -> BTreeMap<DatabaseVersion, ServerMigrationFn> {
     BTreeMap::new()
 }
@@ -129,7 +157,8 @@ mod fedimint_migration_tests {
     use super::{
         get_global_database_migrations, AcceptedItem, AcceptedItemKey, AcceptedItemPrefix,
         AcceptedTransactionKey, AcceptedTransactionKeyPrefix, AlephUnitsKey, AlephUnitsPrefix,
-        DbKeyPrefix, SignedSessionOutcomeKey, SignedSessionOutcomePrefix, GLOBAL_DATABASE_VERSION,
+        DbKeyPrefix, SignedSessionOutcomeKey, SignedSessionOutcomePrefix, SubmittedTransaction,
+        SubmittedTransactionKey, SubmittedTransactionPrefix, GLOBAL_DATABASE_VERSION,
     };
 
     /// Create a database with version 0 data. The database produced is not
@@ -203,6 +232,15 @@ mod fedimint_migration_tests {
         dbtx.insert_new_entry(&AlephUnitsKey(0), &vec![42, 42, 42])
             .await;
 
+        dbtx.insert_new_entry(
+            &SubmittedTransactionKey(transaction.tx_hash()),
+            &SubmittedTransaction {
+                transaction,
+                submitted_at: fedimint_core::time::now(),
+            },
+        )
+        .await;
+
         dbtx.commit_tx().await;
     }
 
@@ -286,6 +324,20 @@ mod fedimint_migration_tests {
                             );
                             info!(target: LOG_DB, "Validated AlephUnits");
                         }
+                        DbKeyPrefix::SubmittedTransaction => {
+                            // Snapshots taken before submitted transactions were persisted
+                            // do not contain any, so we only check that existing ones decode
+                            let submitted_transactions = dbtx
+                                .find_by_prefix(&SubmittedTransactionPrefix)
+                                .await
+                                .collect::<Vec<_>>()
+                                .await;
+                            info!(
+                                target: LOG_DB,
+                                num_submitted_transactions = submitted_transactions.len(),
+                                "Validated SubmittedTransactions"
+                            );
+                        }
                         // Module prefix is reserved for modules, no migration testing is needed
                         DbKeyPrefix::Module => {}
                     }
@@ -1,18 +1,22 @@
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
 
 use aleph_bft::Keychain as KeychainTrait;
 use anyhow::{anyhow, bail};
 use async_channel::Receiver;
+use bitcoin_hashes::sha256;
 use fedimint_api_client::api::{DynGlobalApi, FederationApiExt, PeerConnectionStatus};
 use fedimint_api_client::query::FilterMap;
 use fedimint_core::core::{DynOutput, MODULE_INSTANCE_ID_GLOBAL};
 use fedimint_core::db::{Database, DatabaseTransaction, IDatabaseTransactionOpsCoreTyped};
 use fedimint_core::encoding::Decodable;
-use fedimint_core::endpoint_constants::AWAIT_SIGNED_SESSION_OUTCOME_ENDPOINT;
+use fedimint_core::endpoint_constants::{
+    AWAIT_SIGNED_SESSION_OUTCOME_ENDPOINT, SERVER_CONFIG_CONSENSUS_SECTION_HASHES_ENDPOINT,
+};
 use fedimint_core::epoch::ConsensusItem;
 use fedimint_core::fmt_utils::OptStacktrace;
 use fedimint_core::module::audit::Audit;
@@ -27,8 +31,9 @@ use fedimint_core::timing::TimeReporter;
 use fedimint_core::{timing, NumPeers, NumPeersExt, PeerId};
 use futures::StreamExt;
 use rand::Rng;
-use tokio::sync::{watch, RwLock};
-use tracing::{debug, info, instrument, warn, Level};
+use tokio::sync::{broadcast, watch, RwLock};
+use tokio::time::Instant;
+use tracing::{debug, error, info, info_span, instrument, warn, Instrument, Level};
 
 use crate::config::ServerConfig;
 use crate::consensus::aleph_bft::backup::{BackupReader, BackupWriter};
@@ -43,12 +48,14 @@ use crate::consensus::db::{
     SignedSessionOutcomeKey, SignedSessionOutcomePrefix,
 };
 use crate::consensus::debug::{DebugConsensusItem, DebugConsensusItemCompact};
+use crate::consensus::event::ConsensusEvent;
 use crate::consensus::transaction::process_transaction_with_dbtx;
 use crate::fedimint_core::encoding::Encodable;
 use crate::metrics::{
     CONSENSUS_ITEMS_PROCESSED_TOTAL, CONSENSUS_ITEM_PROCESSING_DURATION_SECONDS,
     CONSENSUS_ITEM_PROCESSING_MODULE_AUDIT_DURATION_SECONDS,
-    CONSENSUS_PEER_CONTRIBUTION_SESSION_IDX, CONSENSUS_SESSION_COUNT,
+    CONSENSUS_PEER_CONTRIBUTION_SESSION_IDX, CONSENSUS_PEER_MISSED_SESSIONS_TOTAL,
+    CONSENSUS_SESSION_COUNT, CONSENSUS_STALLED,
 };
 use crate::net::connect::{Connector, TlsTcpConnector};
 use crate::net::peers::{DelayCalculator, ReconnectPeerConnections};
@@ -57,6 +64,14 @@ use crate::LOG_CONSENSUS;
 // The name of the directory where the database checkpoints are stored.
 const DB_CHECKPOINTS_DIR: &str = "db_checkpoints";
 
+// If the atomic broadcast does not order a single unit for this long we
+// consider consensus to be stalled.
+const CONSENSUS_STALL_TIMEOUT: Duration = Duration::from_secs(60);
+
+// How long we wait for each peer's config section hashes when diagnosing a
+// consensus config mismatch.
+const CONFIG_MISMATCH_REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
 /// Runs the main server consensus loop
 pub struct ConsensusEngine {
     pub modules: ServerModuleRegistry,
@@ -66,6 +81,8 @@ pub struct ConsensusEngine {
     pub submission_receiver: Receiver<ConsensusItem>,
     pub shutdown_receiver: watch::Receiver<Option<u64>>,
     pub last_ci_by_peer: Arc<RwLock<BTreeMap<PeerId, u64>>>,
+    pub consensus_stalled: Arc<AtomicBool>,
+    pub event_sender: broadcast::Sender<ConsensusEvent>,
     /// Just a string version of `cfg.local.identity` for performance
     pub self_id_str: String,
     /// Just a string version of peer ids for performance
@@ -74,6 +91,9 @@ pub struct ConsensusEngine {
     pub task_group: TaskGroup,
     pub data_dir: PathBuf,
     pub checkpoint_retention: u64,
+    /// We warn about a peer once it has not contributed to this many
+    /// consecutive sessions
+    pub peer_missed_sessions_warning_threshold: u64,
 }
 
 impl ConsensusEngine {
@@ -182,6 +202,8 @@ impl ConsensusEngine {
 
             info!(target: LOG_CONSENSUS, "Session {session_index} completed");
 
+            self.record_peer_participation(session_index).await;
+
             if Some(session_index) == self.shutdown_receiver.borrow().to_owned() {
                 info!(target: LOG_CONSENSUS, "Initiating shutdown, waiting for peers to complete the session...");
 
@@ -205,6 +227,8 @@ impl ConsensusEngine {
             match self.federation_api.server_config_consensus_hash().await {
                 Ok(consensus_hash) => {
                     if consensus_hash != our_hash {
+                        self.report_config_mismatch().await;
+
                         bail!("Our consensus config doesn't match peers!")
                     }
 
@@ -221,6 +245,50 @@ impl ConsensusEngine {
         }
     }
 
+    /// Logs which sections of our consensus config differ from each peer's to
+    /// help the operator fix the mismatch
+    async fn report_config_mismatch(&self) {
+        let our_hashes = self.cfg.consensus.section_consensus_hashes();
+
+        for peer in self.num_peers().peer_ids() {
+            if peer == self.identity() {
+                continue;
+            }
+
+            let peer_hashes = self
+                .federation_api
+                .request_single_peer_typed::<BTreeMap<String, sha256::Hash>>(
+                    Some(CONFIG_MISMATCH_REQUEST_TIMEOUT),
+                    SERVER_CONFIG_CONSENSUS_SECTION_HASHES_ENDPOINT.to_string(),
+                    ApiRequestErased::default(),
+                    peer,
+                )
+                .await;
+
+            match peer_hashes {
+                Ok(peer_hashes) => {
+                    let differing_sections = our_hashes
+                        .keys()
+                        .chain(peer_hashes.keys())
+                        .filter(|section| our_hashes.get(*section) != peer_hashes.get(*section))
+                        .collect::<BTreeSet<_>>();
+
+                    if !differing_sections.is_empty() {
+                        error!(
+                            target: LOG_CONSENSUS,
+                            %peer,
+                            ?differing_sections,
+                            "Our consensus config differs from peer"
+                        );
+                    }
+                }
+                Err(e) => {
+                    warn!(target: LOG_CONSENSUS, %peer, "Could not fetch consensus config section hashes: {}", OptStacktrace(e));
+                }
+            }
+        }
+    }
+
     pub async fn run_session(
         &self,
         connections: ReconnectPeerConnections<Message>,
@@ -328,6 +396,10 @@ impl ConsensusEngine {
     ) -> anyhow::Result<SignedSessionOutcome> {
         let mut item_index = 0;
 
+        // We track the time of the last ordered unit outside of the select so that
+        // detecting a stall never cancels the download of the signed session outcome
+        let mut last_ordered_unit = Instant::now();
+
         // We build a session outcome out of the ordered batches until either we have
         // processed broadcast_rounds_per_session rounds or a threshold signed
         // session outcome is obtained from our peers
@@ -336,6 +408,10 @@ impl ConsensusEngine {
                 ordered_unit = ordered_unit_receiver.recv() => {
                     let ordered_unit = ordered_unit?;
 
+                    last_ordered_unit = Instant::now();
+
+                    self.report_unit_ordered();
+
                     if ordered_unit.round >= self.cfg.consensus.broadcast_rounds_per_session {
                         break;
                     }
@@ -356,7 +432,7 @@ impl ConsensusEngine {
                         }
                     }
                 },
-                signed_session_outcome = self.request_signed_session_outcome(&self.federation_api, session_index) => {
+                signed_session_outcome = self.await_signed_session_outcome(session_index, last_ordered_unit, None) => {
                     let pending_accepted_items = self.pending_accepted_items().await;
 
                     // this panics if we have more accepted items than the signed session outcome
@@ -413,6 +489,10 @@ impl ConsensusEngine {
                 ordered_unit = ordered_unit_receiver.recv() => {
                     let ordered_unit = ordered_unit?;
 
+                    last_ordered_unit = Instant::now();
+
+                    self.report_unit_ordered();
+
                     if let Some(UnitData::Signature(signature)) = ordered_unit.data {
                         if keychain.verify(&header, &signature, to_node_index(ordered_unit.creator)){
                             signatures.insert(ordered_unit.creator, signature);
@@ -427,7 +507,11 @@ impl ConsensusEngine {
                         }
                     }
                 }
-                signed_session_outcome = self.request_signed_session_outcome(&self.federation_api, session_index) => {
+                signed_session_outcome = self.await_signed_session_outcome(
+                    session_index,
+                    last_ordered_unit,
+                    Some(signatures.keys().copied().collect())
+                ) => {
                     assert_eq!(
                         header,
                         signed_session_outcome.session_outcome.header(session_index),
@@ -445,6 +529,112 @@ impl ConsensusEngine {
         })
     }
 
+    /// Counts the peers that did not contribute to the completed session and
+    /// warns about the ones that have been silent for several sessions
+    async fn record_peer_participation(&self, session_index: u64) {
+        let last_ci_by_peer = self.last_ci_by_peer.read().await;
+
+        for peer in self.cfg.consensus.broadcast_public_keys.keys() {
+            let last_contribution = last_ci_by_peer.get(peer).copied();
+
+            if last_contribution == Some(session_index) {
+                continue;
+            }
+
+            CONSENSUS_PEER_MISSED_SESSIONS_TOTAL
+                .with_label_values(&[&self.self_id_str, &self.peer_id_str[peer.to_usize()]])
+                .inc();
+
+            if last_contribution.is_none_or(|last| {
+                self.peer_missed_sessions_warning_threshold <= session_index - last
+            }) {
+                warn!(
+                    target: LOG_CONSENSUS,
+                    %peer,
+                    ?last_contribution,
+                    session_index,
+                    "Peer has not contributed to consensus in a while"
+                );
+            }
+        }
+    }
+
+    /// Clears the stall flag once consensus makes progress after a stall
+    fn report_unit_ordered(&self) {
+        if self.consensus_stalled.swap(false, Ordering::Relaxed) {
+            CONSENSUS_STALLED.set(0);
+
+            info!(target: LOG_CONSENSUS, "Consensus resumed after a stall");
+        }
+    }
+
+    /// Downloads the signed session outcome from our peers while reporting a
+    /// consensus stall every [`CONSENSUS_STALL_TIMEOUT`] that passes without a
+    /// unit being ordered. Reporting a stall does not interrupt the download.
+    ///
+    /// While collecting signatures `signed_peers` contains the peers whose
+    /// signature we already received.
+    async fn await_signed_session_outcome(
+        &self,
+        session_index: u64,
+        last_ordered_unit: Instant,
+        signed_peers: Option<BTreeSet<PeerId>>,
+    ) -> SignedSessionOutcome {
+        let signed_session_outcome =
+            self.request_signed_session_outcome(&self.federation_api, session_index);
+
+        tokio::pin!(signed_session_outcome);
+
+        let mut stall_interval = tokio::time::interval_at(
+            last_ordered_unit + CONSENSUS_STALL_TIMEOUT,
+            CONSENSUS_STALL_TIMEOUT,
+        );
+
+        loop {
+            tokio::select! {
+                signed_session_outcome = &mut signed_session_outcome => {
+                    return signed_session_outcome;
+                }
+                _ = stall_interval.tick() => {
+                    self.report_consensus_stall(session_index, signed_peers.as_ref()).await;
+                }
+            }
+        }
+    }
+
+    /// Flags consensus as stalled and logs the peers that have not contributed
+    /// to the current session, as they are the likely cause of the stall. Once
+    /// we collect signatures these are the peers whose signature is missing.
+    async fn report_consensus_stall(
+        &self,
+        session_index: u64,
+        signed_peers: Option<&BTreeSet<PeerId>>,
+    ) {
+        self.consensus_stalled.store(true, Ordering::Relaxed);
+
+        CONSENSUS_STALLED.set(1);
+
+        let last_ci_by_peer = self.last_ci_by_peer.read().await;
+
+        let silent_peers = self
+            .cfg
+            .consensus
+            .broadcast_public_keys
+            .keys()
+            .filter(|peer| match signed_peers {
+                Some(signed_peers) => !signed_peers.contains(peer),
+                None => last_ci_by_peer.get(peer) != Some(&session_index),
+            })
+            .collect::<Vec<_>>();
+
+        warn!(
+            target: LOG_CONSENSUS,
+            session_index,
+            ?silent_peers,
+            "Consensus stalled, no unit was ordered in {CONSENSUS_STALL_TIMEOUT:?}"
+        );
+    }
+
     fn decoders(&self) -> ModuleDecoderRegistry {
         self.modules.decoder_registry()
     }
@@ -485,6 +675,10 @@ impl ConsensusEngine {
         dbtx.commit_tx_result()
             .await
             .expect("This is the only place where we write to this key");
+
+        self.event_sender
+            .send(ConsensusEvent::SessionCompleted { session_index })
+            .ok();
     }
 
     /// Returns the full path where the database checkpoints are stored.
@@ -598,6 +792,15 @@ impl ConsensusEngine {
             .await
             .insert(peer, session_index);
 
+        // A peer contributing invalid items is still participating in consensus, so
+        // we record the contribution before validating the item
+        self.event_sender
+            .send(ConsensusEvent::PeerContribution {
+                session_index,
+                peer,
+            })
+            .ok();
+
         CONSENSUS_PEER_CONTRIBUTION_SESSION_IDX
             .with_label_values(&[&self.self_id_str, peer_id_str])
             .set(session_index as i64);
@@ -627,7 +830,9 @@ impl ConsensusEngine {
         // item has been fully processed without errors
         dbtx.warn_uncommitted();
 
-        dbtx.insert_entry(&AcceptedItemKey(item_index), &AcceptedItem { item, peer })
+        let accepted_item = AcceptedItem { item, peer };
+
+        dbtx.insert_entry(&AcceptedItemKey(item_index), &accepted_item)
             .await;
 
         let mut audit = Audit::default();
@@ -666,6 +871,14 @@ impl ConsensusEngine {
             .inc();
         timing_prom.observe_duration();
 
+        self.event_sender
+            .send(ConsensusEvent::ItemAccepted {
+                session_index,
+                item_index,
+                accepted_item,
+            })
+            .ok();
+
         Ok(())
     }
 
@@ -707,7 +920,10 @@ impl ConsensusEngine {
                     .map(DynOutput::module_instance_id)
                     .collect::<Vec<_>>();
 
+                // The span shares the txid field with the `submit_transaction` span of the
+                // API, which allows to correlate both when exporting the traces
                 process_transaction_with_dbtx(self.modules.clone(), dbtx, &transaction)
+                    .instrument(info_span!(target: LOG_CONSENSUS, "process_transaction", %txid))
                     .await
                     .map_err(|error| anyhow!(error.to_string()))?;
 
@@ -0,0 +1,29 @@
+use fedimint_core::session_outcome::AcceptedItem;
+use fedimint_core::{PeerId, TransactionId};
+
+/// How many events can be buffered for a lagging subscriber before it starts
+/// missing events
+pub const CONSENSUS_EVENT_BUFFER: usize = 1000;
+
+/// Events emitted while running consensus. Components like the API, metrics or
+/// tests can subscribe to them instead of scraping the logs.
+#[derive(Debug, Clone)]
+pub enum ConsensusEvent {
+    /// A valid transaction was submitted to us via the API and will be
+    /// proposed to the atomic broadcast
+    TransactionSubmitted(TransactionId),
+    /// A peer contributed a consensus item to the session. This is emitted
+    /// before the item is validated, so it is also emitted for items that are
+    /// rejected and never show up as [`ConsensusEvent::ItemAccepted`].
+    PeerContribution { session_index: u64, peer: PeerId },
+    /// A consensus item was processed successfully and became part of the
+    /// session outcome
+    ItemAccepted {
+        session_index: u64,
+        item_index: u64,
+        accepted_item: AcceptedItem,
+    },
+    /// A session was completed and its outcome was signed by a threshold of
+    /// peers
+    SessionCompleted { session_index: u64 },
+}
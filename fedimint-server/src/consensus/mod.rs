@@ -5,6 +5,7 @@ pub mod api;
 pub mod db;
 pub mod debug;
 pub mod engine;
+pub mod event;
 pub mod transaction;
 
 use std::collections::BTreeMap;
@@ -13,35 +14,54 @@ use std::path::PathBuf;
 use std::sync::Arc;
 use std::time::Duration;
 
-use anyhow::bail;
+use anyhow::{bail, ensure, Context};
 use async_channel::Sender;
-use db::{get_global_database_migrations, GLOBAL_DATABASE_VERSION};
+use db::{
+    get_global_database_migrations, AcceptedTransactionKey, SubmittedTransactionKey,
+    SubmittedTransactionPrefix, GLOBAL_DATABASE_VERSION,
+};
 use fedimint_api_client::api::DynGlobalApi;
 use fedimint_core::config::ServerModuleInitRegistry;
 use fedimint_core::core::{ModuleInstanceId, ModuleKind};
-use fedimint_core::db::{apply_migrations, apply_migrations_server, Database};
+use fedimint_core::db::{
+    apply_migrations, apply_migrations_server, Database, IDatabaseTransactionOpsCoreTyped,
+};
 use fedimint_core::envs::is_running_in_test_env;
 use fedimint_core::epoch::ConsensusItem;
-use fedimint_core::module::registry::ModuleRegistry;
+use fedimint_core::module::registry::{ModuleRegistry, ServerModuleRegistry};
 use fedimint_core::server::DynServerModule;
 use fedimint_core::task::TaskGroup;
-use fedimint_core::NumPeers;
+use fedimint_core::{NumPeers, TransactionId};
 use fedimint_logging::{LOG_CONSENSUS, LOG_CORE};
+use futures::StreamExt;
 use jsonrpsee::server::ServerHandle;
-use tokio::sync::watch;
-use tracing::info;
+use tokio::sync::{broadcast, watch};
 use tracing::log::warn;
+use tracing::{debug, info};
 
-use crate::config::{ServerConfig, ServerConfigLocal};
+use crate::config::{self, ServerConfig, ServerConfigLocal};
 use crate::consensus::api::ConsensusApi;
 use crate::consensus::engine::ConsensusEngine;
-use crate::envs::{FM_DB_CHECKPOINT_RETENTION_DEFAULT, FM_DB_CHECKPOINT_RETENTION_ENV};
+use crate::consensus::event::CONSENSUS_EVENT_BUFFER;
+use crate::consensus::transaction::process_transaction_with_dbtx;
+use crate::envs::{
+    FM_DB_CHECKPOINT_RETENTION_DEFAULT, FM_DB_CHECKPOINT_RETENTION_ENV,
+    FM_PEER_MISSED_SESSIONS_WARNING_THRESHOLD_DEFAULT,
+    FM_PEER_MISSED_SESSIONS_WARNING_THRESHOLD_ENV,
+    FM_SUBMITTED_TRANSACTION_RESUBMISSION_INTERVAL_SECS_DEFAULT,
+    FM_SUBMITTED_TRANSACTION_RESUBMISSION_INTERVAL_SECS_ENV,
+    FM_SUBMITTED_TRANSACTION_TTL_SECS_DEFAULT, FM_SUBMITTED_TRANSACTION_TTL_SECS_ENV,
+};
+use crate::metrics::{report_database_size, CONSENSUS_SUBMITTED_TRANSACTIONS_DROPPED_TOTAL};
 use crate::net;
 use crate::net::api::{ApiSecrets, RpcHandlerCtx};
 
 /// How many txs can be stored in memory before blocking the API
 const TRANSACTION_BUFFER: usize = 1000;
 
+/// How often we scan the database to report its size per key prefix
+const DATABASE_SIZE_REPORT_INTERVAL: Duration = Duration::from_secs(10 * 60);
+
 pub async fn run(
     cfg: ServerConfig,
     db: Database,
@@ -100,6 +120,8 @@ pub async fn run(
     let (shutdown_sender, shutdown_receiver) = watch::channel(None);
     let connection_status_channels = Default::default();
     let last_ci_by_peer = Default::default();
+    let consensus_stalled = Default::default();
+    let (event_sender, _) = broadcast::channel(CONSENSUS_EVENT_BUFFER);
 
     let consensus_api = ConsensusApi {
         cfg: cfg.clone(),
@@ -113,6 +135,8 @@ pub async fn run(
             &module_init_registry,
         ),
         last_ci_by_peer: Arc::clone(&last_ci_by_peer),
+        consensus_stalled: Arc::clone(&consensus_stalled),
+        event_sender: event_sender.clone(),
         connection_status_channels: Arc::clone(&connection_status_channels),
         force_api_secret: force_api_secrets.get_active(),
     };
@@ -135,12 +159,41 @@ pub async fn run(
         );
     }
 
+    let resubmission_interval = secs_from_env(
+        FM_SUBMITTED_TRANSACTION_RESUBMISSION_INTERVAL_SECS_ENV,
+        FM_SUBMITTED_TRANSACTION_RESUBMISSION_INTERVAL_SECS_DEFAULT,
+    )?;
+
+    let submitted_transaction_ttl = secs_from_env(
+        FM_SUBMITTED_TRANSACTION_TTL_SECS_ENV,
+        FM_SUBMITTED_TRANSACTION_TTL_SECS_DEFAULT,
+    )?;
+
+    resubmit_submitted_transactions(
+        task_group,
+        db.clone(),
+        module_registry.clone(),
+        submission_sender.clone(),
+        resubmission_interval,
+        submitted_transaction_ttl,
+    );
+
+    spawn_database_size_reporting(task_group, db.clone());
+
     let checkpoint_retention: String = env::var(FM_DB_CHECKPOINT_RETENTION_ENV)
         .unwrap_or(FM_DB_CHECKPOINT_RETENTION_DEFAULT.to_string());
     let checkpoint_retention = checkpoint_retention.parse().unwrap_or_else(|_| {
         panic!("FM_DB_CHECKPOINT_RETENTION_ENV var is invalid: {checkpoint_retention}")
     });
 
+    let peer_missed_sessions_warning_threshold: String =
+        env::var(FM_PEER_MISSED_SESSIONS_WARNING_THRESHOLD_ENV)
+            .unwrap_or(FM_PEER_MISSED_SESSIONS_WARNING_THRESHOLD_DEFAULT.to_string());
+    let peer_missed_sessions_warning_threshold =
+        peer_missed_sessions_warning_threshold.parse().unwrap_or_else(|_| {
+            panic!("FM_PEER_MISSED_SESSIONS_WARNING_THRESHOLD_ENV var is invalid: {peer_missed_sessions_warning_threshold}")
+        });
+
     info!(target: LOG_CONSENSUS, "Starting Consensus Engine");
 
     ConsensusEngine {
@@ -155,10 +208,13 @@ pub async fn run(
         submission_receiver,
         shutdown_receiver,
         last_ci_by_peer,
+        consensus_stalled,
+        event_sender,
         modules: module_registry,
         task_group: task_group.clone(),
         data_dir,
         checkpoint_retention,
+        peer_missed_sessions_warning_threshold,
     }
     .run()
     .await?;
@@ -172,6 +228,22 @@ pub async fn run(
     Ok(())
 }
 
+/// Reads a positive number of seconds from the env var `env`, or returns
+/// `default` if it is not set
+fn secs_from_env(env: &str, default: u64) -> anyhow::Result<Duration> {
+    let secs = match env::var(env) {
+        Ok(secs) => secs
+            .parse()
+            .with_context(|| format!("{env} is not a number of seconds: {secs}"))?,
+        Err(env::VarError::NotPresent) => default,
+        Err(error) => bail!("{env} is invalid: {error}"),
+    };
+
+    ensure!(secs != 0, "{env} has to be larger than zero");
+
+    Ok(Duration::from_secs(secs))
+}
+
 async fn start_consensus_api(
     cfg: &ServerConfigLocal,
     api: ConsensusApi,
@@ -190,6 +262,7 @@ async fn start_consensus_api(
         &cfg.api_bind,
         rpc_module,
         cfg.max_connections,
+        config::api_rate_limits(),
         force_api_secrets,
     )
     .await
@@ -250,3 +323,373 @@ fn submit_module_ci_proposals(
         },
     );
 }
+
+/// Periodically resubmits the transactions that were submitted through our API
+/// but not yet accepted by consensus, for example because we shut down before
+/// they were ordered. Transactions that became invalid or were not accepted
+/// within the given ttl are dropped.
+fn resubmit_submitted_transactions(
+    task_group: &TaskGroup,
+    db: Database,
+    modules: ServerModuleRegistry,
+    submission_sender: Sender<ConsensusItem>,
+    resubmission_interval: Duration,
+    ttl: Duration,
+) {
+    let mut interval = tokio::time::interval(resubmission_interval);
+
+    task_group.spawn_cancellable("resubmit submitted transactions", async move {
+        loop {
+            interval.tick().await;
+
+            process_submitted_transactions(&db, &modules, &submission_sender, ttl).await;
+        }
+    });
+}
+
+/// Resubmits every submitted transaction that is still valid and removes the
+/// ones that were accepted by consensus in the meantime, have expired or became
+/// invalid.
+///
+/// The consensus engine does not remove submitted transactions itself, since
+/// the API may write the same key concurrently when a client submits the
+/// transaction to every guardian and a write conflict in consensus is fatal.
+async fn process_submitted_transactions(
+    db: &Database,
+    modules: &ServerModuleRegistry,
+    submission_sender: &Sender<ConsensusItem>,
+    ttl: Duration,
+) {
+    let submitted_transactions = db
+        .begin_transaction_nc()
+        .await
+        .find_by_prefix(&SubmittedTransactionPrefix)
+        .await
+        .map(|(key, submitted)| (key.0, submitted))
+        .collect::<Vec<_>>()
+        .await;
+
+    for (txid, submitted) in submitted_transactions {
+        let mut dbtx = db.begin_transaction_nc().await;
+
+        // We only check if the transaction is still valid and ignore any writes
+        dbtx.ignore_uncommitted();
+
+        if dbtx
+            .get_value(&AcceptedTransactionKey(txid))
+            .await
+            .is_some()
+        {
+            remove_submitted_transaction(db, txid).await;
+
+            continue;
+        }
+
+        let is_expired = submitted.submitted_at.elapsed().is_ok_and(|age| ttl < age);
+
+        if !is_expired
+            && process_transaction_with_dbtx(modules.clone(), &mut dbtx, &submitted.transaction)
+                .await
+                .is_ok()
+        {
+            submission_sender
+                .send(ConsensusItem::Transaction(submitted.transaction))
+                .await
+                .ok();
+
+            continue;
+        }
+
+        if is_expired {
+            debug!(target: LOG_CONSENSUS, %txid, "Dropping expired submitted transaction");
+        }
+
+        CONSENSUS_SUBMITTED_TRANSACTIONS_DROPPED_TOTAL
+            .with_label_values(&[if is_expired { "expired" } else { "invalid" }])
+            .inc();
+
+        remove_submitted_transaction(db, txid).await;
+    }
+}
+
+/// Removes a submitted transaction. If the API persisted the same transaction
+/// concurrently our write conflicts, in which case we retry in the next pass.
+async fn remove_submitted_transaction(db: &Database, txid: TransactionId) {
+    let mut dbtx = db.begin_transaction().await;
+
+    dbtx.remove_entry(&SubmittedTransactionKey(txid)).await;
+
+    if let Err(e) = dbtx.commit_tx_result().await {
+        debug!(target: LOG_CONSENSUS, %txid, %e, "Failed to remove submitted transaction");
+    }
+}
+
+/// Periodically reports the size of the database per key prefix so operators
+/// can see what is consuming disk space
+fn spawn_database_size_reporting(task_group: &TaskGroup, db: Database) {
+    let mut interval = tokio::time::interval(DATABASE_SIZE_REPORT_INTERVAL);
+
+    task_group.spawn_cancellable("report database size", async move {
+        loop {
+            interval.tick().await;
+
+            report_database_size(&db).await;
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+    use std::path::PathBuf;
+    use std::sync::atomic::AtomicBool;
+    use std::sync::Arc;
+    use std::time::{Duration, SystemTime};
+
+    use fedimint_api_client::api::DynGlobalApi;
+    use fedimint_core::config::ServerModuleInitRegistry;
+    use fedimint_core::db::mem_impl::MemDatabase;
+    use fedimint_core::db::{Database, IDatabaseTransactionOpsCoreTyped, IRawDatabaseExt};
+    use fedimint_core::epoch::ConsensusItem;
+    use fedimint_core::module::registry::ServerModuleRegistry;
+    use fedimint_core::session_outcome::{AcceptedItem, SessionOutcome, SignedSessionOutcome};
+    use fedimint_core::task::TaskGroup;
+    use fedimint_core::transaction::{Transaction, TransactionSignature};
+    use fedimint_core::{PeerId, TransactionId};
+    use tokio::sync::{broadcast, watch, RwLock};
+
+    use super::process_submitted_transactions;
+    use crate::config::tests::single_guardian_config;
+    use crate::config::ServerConfig;
+    use crate::consensus::api::ConsensusApi;
+    use crate::consensus::db::{
+        AcceptedTransactionKey, SubmittedTransaction, SubmittedTransactionKey,
+    };
+    use crate::consensus::engine::ConsensusEngine;
+    use crate::consensus::event::{ConsensusEvent, CONSENSUS_EVENT_BUFFER};
+    use crate::envs::FM_SUBMITTED_TRANSACTION_TTL_SECS_DEFAULT;
+
+    const TTL: Duration = Duration::from_secs(FM_SUBMITTED_TRANSACTION_TTL_SECS_DEFAULT);
+
+    /// A transaction without inputs and outputs is valid without any modules
+    fn empty_transaction() -> Transaction {
+        Transaction {
+            inputs: vec![],
+            outputs: vec![],
+            nonce: [0; 8],
+            signatures: TransactionSignature::NaiveMultisig(vec![]),
+        }
+    }
+
+    async fn persist_submitted_transaction(
+        db: &Database,
+        transaction: Transaction,
+        submitted_at: SystemTime,
+    ) -> TransactionId {
+        let txid = transaction.tx_hash();
+        let mut dbtx = db.begin_transaction().await;
+
+        dbtx.insert_entry(
+            &SubmittedTransactionKey(txid),
+            &SubmittedTransaction {
+                transaction,
+                submitted_at,
+            },
+        )
+        .await;
+
+        dbtx.commit_tx().await;
+
+        txid
+    }
+
+    async fn is_submitted(db: &Database, txid: TransactionId) -> bool {
+        db.begin_transaction_nc()
+            .await
+            .get_value(&SubmittedTransactionKey(txid))
+            .await
+            .is_some()
+    }
+
+    #[tokio::test]
+    async fn accepted_submitted_transaction_is_removed() {
+        let db = MemDatabase::new().into_database();
+        let (submission_sender, submission_receiver) = async_channel::unbounded();
+
+        let txid =
+            persist_submitted_transaction(&db, empty_transaction(), fedimint_core::time::now())
+                .await;
+
+        let mut dbtx = db.begin_transaction().await;
+        dbtx.insert_entry(&AcceptedTransactionKey(txid), &vec![])
+            .await;
+        dbtx.commit_tx().await;
+
+        process_submitted_transactions(
+            &db,
+            &ServerModuleRegistry::default(),
+            &submission_sender,
+            TTL,
+        )
+        .await;
+
+        assert!(submission_receiver.is_empty());
+        assert!(!is_submitted(&db, txid).await);
+    }
+
+    #[tokio::test]
+    async fn submitted_transaction_is_resubmitted_after_restart() {
+        let db = MemDatabase::new().into_database();
+
+        let txid =
+            persist_submitted_transaction(&db, empty_transaction(), fedimint_core::time::now())
+                .await;
+
+        // After a restart the submission channel starts out empty
+        let (submission_sender, submission_receiver) = async_channel::unbounded();
+
+        process_submitted_transactions(
+            &db,
+            &ServerModuleRegistry::default(),
+            &submission_sender,
+            TTL,
+        )
+        .await;
+
+        assert_eq!(
+            submission_receiver.try_recv(),
+            Ok(ConsensusItem::Transaction(empty_transaction()))
+        );
+        assert!(is_submitted(&db, txid).await);
+    }
+
+    #[tokio::test]
+    async fn expired_submitted_transaction_is_dropped() {
+        let db = MemDatabase::new().into_database();
+        let (submission_sender, submission_receiver) = async_channel::unbounded();
+
+        let txid = persist_submitted_transaction(
+            &db,
+            empty_transaction(),
+            fedimint_core::time::now() - 2 * TTL,
+        )
+        .await;
+
+        process_submitted_transactions(
+            &db,
+            &ServerModuleRegistry::default(),
+            &submission_sender,
+            TTL,
+        )
+        .await;
+
+        assert!(submission_receiver.is_empty());
+        assert!(!is_submitted(&db, txid).await);
+    }
+
+    #[tokio::test]
+    async fn consensus_events_follow_a_submitted_transaction() {
+        let cfg = single_guardian_config();
+        let db = MemDatabase::new().into_database();
+        let client_cfg = cfg
+            .consensus
+            .to_client_config(&ServerModuleInitRegistry::default())
+            .unwrap();
+
+        let (submission_sender, submission_receiver) = async_channel::unbounded();
+        let (shutdown_sender, shutdown_receiver) = watch::channel(None);
+        let connection_status_channels = Arc::new(RwLock::new(BTreeMap::new()));
+        let last_ci_by_peer = Arc::new(RwLock::new(BTreeMap::new()));
+        let consensus_stalled = Arc::new(AtomicBool::new(false));
+        let (event_sender, _) = broadcast::channel(CONSENSUS_EVENT_BUFFER);
+
+        let api = ConsensusApi {
+            cfg: cfg.clone(),
+            db: db.clone(),
+            modules: ServerModuleRegistry::default(),
+            client_cfg: client_cfg.clone(),
+            force_api_secret: None,
+            submission_sender,
+            shutdown_sender,
+            connection_status_channels: connection_status_channels.clone(),
+            last_ci_by_peer: last_ci_by_peer.clone(),
+            consensus_stalled: consensus_stalled.clone(),
+            event_sender: event_sender.clone(),
+            supported_api_versions: ServerConfig::supported_api_versions_summary(
+                &cfg.consensus.modules,
+                &ServerModuleInitRegistry::default(),
+            ),
+        };
+
+        let engine = ConsensusEngine {
+            modules: ServerModuleRegistry::default(),
+            db: db.clone(),
+            federation_api: DynGlobalApi::from_config(&client_cfg, &None),
+            cfg: cfg.clone(),
+            submission_receiver,
+            shutdown_receiver,
+            last_ci_by_peer,
+            consensus_stalled,
+            event_sender,
+            self_id_str: "0".to_string(),
+            peer_id_str: vec!["0".to_string()],
+            connection_status_channels,
+            task_group: TaskGroup::new(),
+            data_dir: PathBuf::new(),
+            checkpoint_retention: 1,
+            peer_missed_sessions_warning_threshold: 2,
+        };
+
+        let mut events = api.subscribe_consensus_events();
+
+        let txid = api.submit_transaction(empty_transaction()).await.unwrap();
+
+        let item = engine.submission_receiver.recv().await.unwrap();
+
+        engine
+            .process_consensus_item(0, 0, item, PeerId::from(0))
+            .await
+            .unwrap();
+
+        let session_outcome = SessionOutcome {
+            items: engine.pending_accepted_items().await,
+        };
+
+        engine
+            .complete_session(
+                0,
+                SignedSessionOutcome {
+                    session_outcome,
+                    signatures: BTreeMap::new(),
+                },
+            )
+            .await;
+
+        assert!(matches!(
+            events.recv().await,
+            Ok(ConsensusEvent::TransactionSubmitted(submitted)) if submitted == txid
+        ));
+        assert!(matches!(
+            events.recv().await,
+            Ok(ConsensusEvent::PeerContribution {
+                session_index: 0,
+                ..
+            })
+        ));
+        assert!(matches!(
+            events.recv().await,
+            Ok(ConsensusEvent::ItemAccepted {
+                session_index: 0,
+                item_index: 0,
+                accepted_item: AcceptedItem {
+                    item: ConsensusItem::Transaction(accepted),
+                    ..
+                },
+            }) if accepted.tx_hash() == txid
+        ));
+        assert!(matches!(
+            events.recv().await,
+            Ok(ConsensusEvent::SessionCompleted { session_index: 0 })
+        ));
+    }
+}
@@ -100,3 +100,53 @@ impl Default for FundingVerifier {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use fedimint_core::module::TransactionItemAmount;
+    use fedimint_core::Amount;
+
+    use super::FundingVerifier;
+
+    fn item_amount(amount: u64, fee: u64) -> TransactionItemAmount {
+        TransactionItemAmount {
+            amount: Amount::from_msats(amount),
+            fee: Amount::from_msats(fee),
+        }
+    }
+
+    #[test]
+    fn funding_verifier_accepts_balanced_transaction() {
+        let mut verifier = FundingVerifier::default();
+
+        verifier.add_input(item_amount(10_000, 10));
+        verifier.add_input(item_amount(5_000, 10));
+        verifier.add_output(item_amount(14_970, 10));
+
+        assert!(verifier.verify_funding().is_ok());
+    }
+
+    #[test]
+    fn funding_verifier_rejects_unbalanced_transactions() {
+        // (input, input fee, output, output fee), balanced at 10_000 = 9_980 + 10 + 10
+        let cases = [
+            // input is one msat larger than outputs and fees
+            (10_001, 10, 9_980, 10),
+            // input is one msat smaller than outputs and fees
+            (9_999, 10, 9_980, 10),
+            // fees are one msat larger than the difference of input and output
+            (10_000, 11, 9_980, 10),
+            // fees are one msat smaller than the difference of input and output
+            (10_000, 10, 9_980, 9),
+        ];
+
+        for (input, input_fee, output, output_fee) in cases {
+            let mut verifier = FundingVerifier::default();
+
+            verifier.add_input(item_amount(input, input_fee));
+            verifier.add_output(item_amount(output, output_fee));
+
+            assert!(verifier.verify_funding().is_err());
+        }
+    }
+}
@@ -1,5 +1,14 @@
 /// The env var for maximum open connections the API can handle
 pub const FM_MAX_CLIENT_CONNECTIONS_ENV: &str = "FM_MAX_CLIENT_CONNECTIONS";
+/// The env var for the maximum number of transactions a single client ip
+/// address may submit per second. Behind a reverse proxy all clients share the
+/// proxy's address, so [`FM_API_TRUST_FORWARDED_FOR_ENV`] has to be set as
+/// well or the limit applies to all clients together.
+pub const FM_SUBMIT_TRANSACTION_RATE_LIMIT_ENV: &str = "FM_SUBMIT_TRANSACTION_RATE_LIMIT";
+/// The env var that makes the API identify clients by the last address in the
+/// `X-Forwarded-For` header. Only set this if the API is only reachable through
+/// a reverse proxy that sets the header, otherwise clients can spoof it.
+pub const FM_API_TRUST_FORWARDED_FOR_ENV: &str = "FM_API_TRUST_FORWARDED_FOR";
 pub const FM_PEER_ID_SORT_BY_URL_ENV: &str = "FM_PEER_ID_SORT_BY_URL";
 
 /// Environment variable for the session count determining when to cleanup old
@@ -9,3 +18,28 @@ pub const FM_DB_CHECKPOINT_RETENTION_ENV: &str = "FM_DB_CHECKPOINT_RETENTION";
 // Default number of checkpoints from the current session should be retained on
 // disk.
 pub const FM_DB_CHECKPOINT_RETENTION_DEFAULT: u64 = 1;
+
+/// Environment variable for the number of consecutive sessions a peer may miss
+/// before we warn that it is not contributing to consensus
+pub const FM_PEER_MISSED_SESSIONS_WARNING_THRESHOLD_ENV: &str =
+    "FM_PEER_MISSED_SESSIONS_WARNING_THRESHOLD";
+
+// Default number of consecutive sessions a peer may miss before we warn
+pub const FM_PEER_MISSED_SESSIONS_WARNING_THRESHOLD_DEFAULT: u64 = 2;
+
+/// Environment variable for the interval in seconds at which submitted
+/// transactions that were not accepted yet are resubmitted, has to be larger
+/// than zero
+pub const FM_SUBMITTED_TRANSACTION_RESUBMISSION_INTERVAL_SECS_ENV: &str =
+    "FM_SUBMITTED_TRANSACTION_RESUBMISSION_INTERVAL_SECS";
+
+// Default interval in seconds at which submitted transactions are resubmitted
+pub const FM_SUBMITTED_TRANSACTION_RESUBMISSION_INTERVAL_SECS_DEFAULT: u64 = 60;
+
+/// Environment variable for the time in seconds after which a submitted
+/// transaction that was not accepted is no longer resubmitted, has to be larger
+/// than zero
+pub const FM_SUBMITTED_TRANSACTION_TTL_SECS_ENV: &str = "FM_SUBMITTED_TRANSACTION_TTL_SECS";
+
+// Default time in seconds after which submitted transactions expire
+pub const FM_SUBMITTED_TRANSACTION_TTL_SECS_DEFAULT: u64 = 60 * 60;
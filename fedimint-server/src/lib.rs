@@ -18,7 +18,7 @@ extern crate fedimint_core;
 use std::fs;
 use std::path::{Path, PathBuf};
 
-use config::io::{read_server_config, PLAINTEXT_PASSWORD};
+use config::io::{read_server_config_with_plaintext_password, PLAINTEXT_PASSWORD};
 use config::ServerConfig;
 use fedimint_aead::random_salt;
 use fedimint_core::config::ServerModuleInitRegistry;
@@ -33,6 +33,7 @@ use tracing::info;
 use crate::config::api::{ConfigGenApi, ConfigGenSettings};
 use crate::config::io::{write_server_config, SALT_FILE};
 use crate::metrics::initialize_gauge_metrics;
+use crate::net::api::rate_limit::RateLimits;
 use crate::net::api::RpcHandlerCtx;
 use crate::net::connect::TlsTcpConnector;
 
@@ -105,11 +106,7 @@ pub async fn run(
 
 pub fn get_config(data_dir: &Path) -> anyhow::Result<Option<ServerConfig>> {
     // Attempt get the config with local password, otherwise start config gen
-    if let Ok(password) = fs::read_to_string(data_dir.join(PLAINTEXT_PASSWORD)) {
-        return Ok(Some(read_server_config(&password, data_dir)?));
-    }
-
-    Ok(None)
+    read_server_config_with_plaintext_password(data_dir)
 }
 
 pub async fn run_config_gen(
@@ -144,6 +141,7 @@ pub async fn run_config_gen(
         &settings.api_bind,
         rpc_module,
         10,
+        RateLimits::new(),
         force_api_secrets.clone(),
     )
     .await;
@@ -1,7 +1,15 @@
 pub(crate) mod jsonrpsee;
 
+use std::collections::BTreeMap;
+
 use fedimint_core::backup::ClientBackupKeyPrefix;
-use fedimint_core::db::{Database, IDatabaseTransactionOpsCoreTyped};
+use fedimint_core::core::ModuleInstanceId;
+use fedimint_core::db::{
+    Database, IDatabaseTransactionOpsCore, IDatabaseTransactionOpsCoreTyped, MODULE_GLOBAL_PREFIX,
+};
+use fedimint_core::encoding::Decodable;
+use fedimint_core::module::registry::ModuleDecoderRegistry;
+use fedimint_core::transaction::TransactionError;
 use fedimint_metrics::prometheus::{
     register_histogram_vec_with_registry, register_int_gauge_vec_with_registry,
     register_int_gauge_with_registry, HistogramVec, IntCounterVec, IntGauge, IntGaugeVec,
@@ -12,6 +20,9 @@ use fedimint_metrics::{
 };
 use futures::StreamExt as _;
 use once_cell::sync::Lazy;
+use strum::IntoEnumIterator;
+
+use crate::consensus::db::DbKeyPrefix;
 
 pub static TX_ELEMS_BUCKETS: Lazy<Vec<f64>> = Lazy::new(|| {
     vec![
@@ -97,6 +108,30 @@ pub(crate) static JSONRPC_API_REQUEST_RESPONSE_CODE: Lazy<IntCounterVec> = Lazy:
     )
     .unwrap()
 });
+pub(crate) static CONSENSUS_SUBMITTED_TRANSACTIONS_DROPPED_TOTAL: Lazy<IntCounterVec> =
+    Lazy::new(|| {
+        register_int_counter_vec_with_registry!(
+            opts!(
+                "consensus_submitted_transactions_dropped_total",
+                "Submitted transactions that were dropped before being accepted by consensus",
+            ),
+            &["reason"],
+            REGISTRY
+        )
+        .unwrap()
+    });
+pub(crate) static CONSENSUS_SUBMITTED_TRANSACTIONS_REJECTED_TOTAL: Lazy<IntCounterVec> =
+    Lazy::new(|| {
+        register_int_counter_vec_with_registry!(
+            opts!(
+                "consensus_submitted_transactions_rejected_total",
+                "Transactions submitted via the API that were rejected as invalid",
+            ),
+            &["reason"],
+            REGISTRY
+        )
+        .unwrap()
+    });
 pub(crate) static CONSENSUS_SESSION_COUNT: Lazy<IntGauge> = Lazy::new(|| {
     register_int_gauge_with_registry!(
         opts!(
@@ -118,6 +153,17 @@ pub(crate) static CONSENSUS_PEER_CONTRIBUTION_SESSION_IDX: Lazy<IntGaugeVec> = L
     )
     .unwrap()
 });
+pub(crate) static CONSENSUS_PEER_MISSED_SESSIONS_TOTAL: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec_with_registry!(
+        opts!(
+            "consensus_peer_missed_sessions_total",
+            "Number of completed sessions a peer did not contribute to",
+        ),
+        &["self_id", "peer_id"],
+        REGISTRY
+    )
+    .unwrap()
+});
 pub(crate) static BACKUP_WRITE_SIZE_BYTES: Lazy<Histogram> = Lazy::new(|| {
     register_histogram_with_registry!(
         histogram_opts!(
@@ -129,6 +175,16 @@ pub(crate) static BACKUP_WRITE_SIZE_BYTES: Lazy<Histogram> = Lazy::new(|| {
     )
     .unwrap()
 });
+pub(crate) static CONSENSUS_STALLED: Lazy<IntGauge> = Lazy::new(|| {
+    register_int_gauge_with_registry!(
+        opts!(
+            "consensus_stalled",
+            "Whether no unit was ordered by consensus for a while (1) or not (0)",
+        ),
+        REGISTRY
+    )
+    .unwrap()
+});
 pub(crate) static STORED_BACKUPS_COUNT: Lazy<IntGauge> = Lazy::new(|| {
     register_int_gauge_with_registry!(
         opts!("stored_backups_count", "Total amount of backups stored",),
@@ -172,6 +228,88 @@ pub(crate) static PEER_BANS_COUNT: Lazy<IntCounterVec> = Lazy::new(|| {
     .unwrap()
 });
 
+pub(crate) static DATABASE_ENTRIES: Lazy<IntGaugeVec> = Lazy::new(|| {
+    register_int_gauge_vec_with_registry!(
+        opts!(
+            "database_entries",
+            "Number of database entries by key prefix",
+        ),
+        &["module", "prefix"],
+        REGISTRY
+    )
+    .unwrap()
+});
+pub(crate) static DATABASE_SIZE_BYTES: Lazy<IntGaugeVec> = Lazy::new(|| {
+    register_int_gauge_vec_with_registry!(
+        opts!(
+            "database_size_bytes",
+            "Size of the keys and values in the database by key prefix",
+        ),
+        &["module", "prefix"],
+        REGISTRY
+    )
+    .unwrap()
+});
+
+/// Scans the whole database and reports the number and size of its entries
+/// per key prefix. Module entries are reported per module instance with the
+/// first byte of the key within the module as prefix.
+pub(crate) async fn report_database_size(db: &Database) {
+    let mut dbtx = db.begin_transaction_nc().await;
+
+    let mut entries = dbtx.raw_find_by_prefix(&[]).await.expect("Database error");
+
+    let mut sizes = BTreeMap::<(String, String), (i64, i64)>::new();
+
+    while let Some((key, value)) = entries.next().await {
+        let labels = database_size_labels(&key);
+        let (count, size) = sizes.entry(labels).or_default();
+
+        *count += 1;
+        *size += (key.len() + value.len()) as i64;
+    }
+
+    // Prefixes whose entries were all removed since the last scan would otherwise
+    // keep reporting their last value
+    DATABASE_ENTRIES.reset();
+    DATABASE_SIZE_BYTES.reset();
+
+    for ((module, prefix), (count, size)) in sizes {
+        DATABASE_ENTRIES
+            .with_label_values(&[&module, &prefix])
+            .set(count);
+        DATABASE_SIZE_BYTES
+            .with_label_values(&[&module, &prefix])
+            .set(size);
+    }
+}
+
+fn database_size_labels(key: &[u8]) -> (String, String) {
+    let Some((&prefix, mut rest)) = key.split_first() else {
+        return ("global".to_string(), "empty".to_string());
+    };
+
+    if prefix != MODULE_GLOBAL_PREFIX {
+        let prefix = DbKeyPrefix::iter()
+            .find(|known_prefix| known_prefix.clone() as u8 == prefix)
+            .map_or_else(
+                || format!("{prefix:#04x}"),
+                |known_prefix| known_prefix.to_string(),
+            );
+
+        return ("global".to_string(), prefix);
+    }
+
+    match ModuleInstanceId::consensus_decode(&mut rest, &ModuleDecoderRegistry::default()) {
+        Ok(module_instance_id) => (
+            module_instance_id.to_string(),
+            rest.first()
+                .map_or_else(|| "empty".to_string(), |prefix| format!("{prefix:#04x}")),
+        ),
+        Err(_) => ("global".to_string(), DbKeyPrefix::Module.to_string()),
+    }
+}
+
 /// Initialize gauges or other metrics that need eager initialization on start,
 /// e.g. because they are triggered infrequently.
 pub(crate) async fn initialize_gauge_metrics(db: &Database) {
@@ -184,3 +322,18 @@ pub(crate) async fn initialize_gauge_metrics(db: &Database) {
             .await as i64,
     );
 }
+
+/// Label of a [`TransactionError`] for
+/// [`CONSENSUS_SUBMITTED_TRANSACTIONS_REJECTED_TOTAL`], kept free of any
+/// transaction specific data to bound the cardinality of the metric
+pub(crate) fn transaction_rejection_reason(error: &TransactionError) -> &'static str {
+    match error {
+        TransactionError::UnbalancedTransaction { .. } => "unbalanced",
+        TransactionError::InvalidSignature { .. } => "invalid_signature",
+        TransactionError::UnsupportedSignatureScheme { .. } => "unsupported_signature_scheme",
+        TransactionError::InvalidWitnessLength => "invalid_witness_length",
+        TransactionError::Input(_) => "invalid_input",
+        TransactionError::Output(_) => "invalid_output",
+        TransactionError::TooLarge { .. } => "too_large",
+    }
+}
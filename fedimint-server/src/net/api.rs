@@ -1,7 +1,8 @@
 mod http_auth;
+pub mod rate_limit;
 
 use std::fmt::{self, Debug, Formatter};
-use std::net::SocketAddr;
+use std::net::{IpAddr, SocketAddr};
 use std::panic::AssertUnwindSafe;
 use std::str::FromStr;
 use std::sync::Arc;
@@ -11,16 +12,27 @@ use anyhow::{bail, Context};
 use async_trait::async_trait;
 use fedimint_core::core::ModuleInstanceId;
 use fedimint_core::encoding::{Decodable, Encodable};
+use fedimint_core::envs::is_env_var_set;
 use fedimint_core::module::{ApiEndpoint, ApiEndpointContext, ApiError, ApiRequestErased};
+use fedimint_core::runtime;
+use fedimint_core::task::sleep;
 use fedimint_logging::LOG_NET_API;
 use futures::FutureExt;
-use jsonrpsee::server::{PingConfig, RpcServiceBuilder, ServerBuilder, ServerHandle};
+use hyper::body::Incoming;
+use jsonrpsee::server::{
+    serve_with_graceful_shutdown, stop_channel, HttpRequest, Methods, PingConfig,
+    RpcServiceBuilder, ServerBuilder, ServerHandle,
+};
 use jsonrpsee::types::ErrorObject;
 use jsonrpsee::RpcModule;
-use tracing::{error, info};
+use tokio::net::TcpListener;
+use tower::Service;
+use tracing::{debug, error, info};
 
+use crate::envs::FM_API_TRUST_FORWARDED_FOR_ENV;
 use crate::metrics;
 use crate::net::api::http_auth::HttpAuthLayer;
+use crate::net::api::rate_limit::{RateLimitLayer, RateLimits};
 
 #[derive(Clone, Encodable, Decodable, Default)]
 pub struct ApiSecrets(Vec<String>);
@@ -99,6 +111,9 @@ impl<M: Debug> Debug for RpcHandlerCtx<M> {
 /// How long to wait before timing out client connections
 const API_ENDPOINT_TIMEOUT: Duration = Duration::from_secs(60);
 
+/// How long the api waits before accepting connections again after an error
+const ACCEPT_ERROR_BACKOFF: Duration = Duration::from_millis(100);
+
 /// Has the context necessary for serving API endpoints
 ///
 /// Returns the specific `State` the endpoint requires and the
@@ -127,6 +142,7 @@ pub async fn spawn<T>(
     api_bind: &SocketAddr,
     module: RpcModule<RpcHandlerCtx<T>>,
     max_connections: u32,
+    rate_limits: RateLimits,
     force_api_secrets: ApiSecrets,
 ) -> ServerHandle {
     info!(target: LOG_NET_API, "Starting api on ws://{api_bind}");
@@ -134,17 +150,101 @@ pub async fn spawn<T>(
     let builder =
         tower::ServiceBuilder::new().layer(HttpAuthLayer::new(force_api_secrets.get_all()));
 
-    ServerBuilder::new()
+    let service_builder = ServerBuilder::new()
         .max_connections(max_connections)
         .enable_ws_ping(PingConfig::new().ping_interval(Duration::from_secs(10)))
-        .set_rpc_middleware(RpcServiceBuilder::new().layer(metrics::jsonrpsee::MetricsLayer))
+        .set_rpc_middleware(
+            RpcServiceBuilder::new()
+                .layer(metrics::jsonrpsee::MetricsLayer)
+                .layer(RateLimitLayer::new(rate_limits)),
+        )
         .set_http_middleware(builder)
-        .build(&api_bind.to_string())
+        .to_service_builder();
+
+    let listener = TcpListener::bind(api_bind)
         .await
         .context(format!("Bind address: {api_bind}"))
         .context(format!("API name: {name}"))
-        .expect("Could not build API server")
-        .start(module)
+        .expect("Could not build API server");
+
+    let trust_forwarded_for = is_env_var_set(FM_API_TRUST_FORWARDED_FOR_ENV);
+
+    let (stop_handle, server_handle) = stop_channel();
+    let methods = Methods::from(module);
+
+    // We run the accept loop ourselves instead of using `Server::start` so we
+    // can attach the ip address of the client to its requests, which is what
+    // the rate limiter keys its token buckets by.
+    // Our database is open and we serve the api, so we can be put behind a load
+    // balancer. This does not depend on the state of consensus, since a guardian
+    // has to serve its api to recover from a stall or to finish config gen.
+    fedimint_metrics::set_ready(true);
+
+    runtime::spawn("api accept loop", async move {
+        loop {
+            let (socket, remote_addr) = tokio::select! {
+                res = listener.accept() => match res {
+                    Ok(conn) => conn,
+                    Err(e) => {
+                        debug!(target: LOG_NET_API, %e, "Failed to accept api connection");
+
+                        // Errors like running out of file descriptors persist for a while,
+                        // so we back off instead of retrying in a busy loop
+                        sleep(ACCEPT_ERROR_BACKOFF).await;
+
+                        continue;
+                    }
+                },
+                () = stop_handle.clone().shutdown() => {
+                    fedimint_metrics::set_ready(false);
+
+                    break;
+                }
+            };
+
+            let service = service_builder
+                .clone()
+                .build(methods.clone(), stop_handle.clone());
+
+            let service = tower::service_fn(move |mut req: HttpRequest<Incoming>| {
+                let client_ip = trust_forwarded_for
+                    .then(|| forwarded_for(&req))
+                    .flatten()
+                    .unwrap_or(remote_addr.ip());
+
+                req.extensions_mut().insert(client_ip);
+
+                let mut service = service.clone();
+                async move {
+                    service
+                        .call(req)
+                        .await
+                        .map_err(|e| anyhow::anyhow!("{e:?}"))
+                }
+            });
+
+            runtime::spawn(
+                "api connection",
+                serve_with_graceful_shutdown(socket, service, stop_handle.clone().shutdown()),
+            );
+        }
+    });
+
+    server_handle
+}
+
+/// Returns the last address in the `X-Forwarded-For` header, which is the one
+/// appended by the reverse proxy in front of us
+fn forwarded_for<B>(req: &HttpRequest<B>) -> Option<IpAddr> {
+    req.headers()
+        .get("x-forwarded-for")?
+        .to_str()
+        .ok()?
+        .rsplit(',')
+        .next()?
+        .trim()
+        .parse()
+        .ok()
 }
 
 pub fn attach_endpoints<State, T>(
@@ -209,3 +309,34 @@ pub fn attach_endpoints<State, T>(
             .expect("Failed to register async method");
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::net::IpAddr;
+
+    use jsonrpsee::server::HttpRequest;
+
+    use super::forwarded_for;
+
+    #[test]
+    fn forwarded_for_uses_address_appended_by_proxy() {
+        let request = |header: Option<&str>| {
+            let mut builder = HttpRequest::builder();
+            if let Some(header) = header {
+                builder = builder.header("x-forwarded-for", header);
+            }
+            builder.body(()).unwrap()
+        };
+
+        assert_eq!(
+            forwarded_for(&request(Some("1.1.1.1, 2.2.2.2"))),
+            Some("2.2.2.2".parse::<IpAddr>().unwrap())
+        );
+        assert_eq!(
+            forwarded_for(&request(Some("::1"))),
+            Some("::1".parse::<IpAddr>().unwrap())
+        );
+        assert_eq!(forwarded_for(&request(Some("not an ip"))), None);
+        assert_eq!(forwarded_for(&request(None)), None);
+    }
+}
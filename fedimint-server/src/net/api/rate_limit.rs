@@ -0,0 +1,199 @@
+//! jsonrpsee/tower rpc layer that rate limits expensive rpc methods
+//!
+//! The token buckets are keyed by the ip address of the client and shared
+//! between all of its connections, so opening more connections does not raise
+//! the limit. This makes it impossible for a single client to flood us with
+//! requests like `submit_transaction`, which have to be fully validated before
+//! we can reject them.
+//!
+//! Behind a reverse proxy the address is taken from the `X-Forwarded-For`
+//! header if `FM_API_TRUST_FORWARDED_FOR` is set, otherwise all clients share
+//! the proxy's bucket.
+
+use std::collections::{BTreeMap, HashMap};
+use std::net::IpAddr;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+use fedimint_logging::LOG_NET_API;
+use futures::future::{ready, Either, Ready};
+use jsonrpsee::server::middleware::rpc::RpcServiceT;
+use jsonrpsee::types::{ErrorObject, Request};
+use jsonrpsee::MethodResponse;
+use tracing::debug;
+
+/// JSON-RPC error code returned when a request was rejected due to the rate
+/// limit of its method being exceeded
+pub const RATE_LIMIT_EXCEEDED_ERROR_CODE: i32 = -32005;
+
+/// Maximum number of requests per second a single ip address may send for a
+/// given rpc method, keyed by method name
+pub type RateLimits = BTreeMap<String, u32>;
+
+#[derive(Debug)]
+struct TokenBucket {
+    capacity: f64,
+    tokens: f64,
+    last_refill: Instant,
+}
+
+impl TokenBucket {
+    fn new(requests_per_second: u32) -> Self {
+        Self {
+            capacity: f64::from(requests_per_second),
+            tokens: f64::from(requests_per_second),
+            last_refill: Instant::now(),
+        }
+    }
+
+    /// Refills the bucket according to the time elapsed since the last refill
+    /// and tries to take a token out of it
+    fn try_acquire(&mut self) -> bool {
+        let now = Instant::now();
+        let elapsed = now.duration_since(self.last_refill).as_secs_f64();
+
+        self.tokens = (self.tokens + elapsed * self.capacity).min(self.capacity);
+        self.last_refill = now;
+
+        if 1.0 <= self.tokens {
+            self.tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// A bucket that has been refilled completely behaves exactly like a new
+    /// one, so it can be dropped without loosening the limit
+    fn is_full(&self) -> bool {
+        let elapsed = self.last_refill.elapsed().as_secs_f64();
+
+        self.capacity <= self.tokens + elapsed * self.capacity
+    }
+}
+
+type TokenBuckets = HashMap<(IpAddr, String), TokenBucket>;
+
+#[derive(Clone, Debug)]
+pub struct RateLimitLayer {
+    limits: Arc<RateLimits>,
+    buckets: Arc<Mutex<TokenBuckets>>,
+}
+
+impl RateLimitLayer {
+    pub fn new(limits: RateLimits) -> Self {
+        Self {
+            limits: Arc::new(limits),
+            buckets: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+}
+
+impl<S> tower::Layer<S> for RateLimitLayer {
+    type Service = RateLimitService<S>;
+
+    fn layer(&self, service: S) -> Self::Service {
+        RateLimitService {
+            service,
+            limits: self.limits.clone(),
+            buckets: self.buckets.clone(),
+        }
+    }
+}
+
+pub struct RateLimitService<S> {
+    service: S,
+    limits: Arc<RateLimits>,
+    buckets: Arc<Mutex<TokenBuckets>>,
+}
+
+impl<S> RateLimitService<S> {
+    fn try_acquire(&self, ip: IpAddr, method: &str) -> bool {
+        let Some(limit) = self.limits.get(method) else {
+            return true;
+        };
+
+        let mut buckets = self.buckets.lock().expect("poisoned");
+        let key = (ip, method.to_owned());
+
+        if !buckets.contains_key(&key) {
+            // Only prune when a new client shows up to keep the map bounded by the
+            // number of clients that are currently sending requests
+            buckets.retain(|_, bucket| !bucket.is_full());
+        }
+
+        buckets
+            .entry(key)
+            .or_insert_with(|| TokenBucket::new(*limit))
+            .try_acquire()
+    }
+}
+
+impl<'a, S> RpcServiceT<'a> for RateLimitService<S>
+where
+    S: RpcServiceT<'a> + Send + Sync,
+{
+    type Future = Either<S::Future, Ready<MethodResponse>>;
+
+    fn call(&self, req: Request<'a>) -> Self::Future {
+        // The client ip is attached to every request by the api accept loop, if it is
+        // missing anyway we let the request through rather than failing it
+        let Some(ip) = req.extensions().get::<IpAddr>().copied() else {
+            return Either::Left(self.service.call(req));
+        };
+
+        if !self.try_acquire(ip, req.method_name()) {
+            debug!(target: LOG_NET_API, %ip, method = %req.method_name(), "Rate limit exceeded");
+
+            let error = ErrorObject::owned(
+                RATE_LIMIT_EXCEEDED_ERROR_CODE,
+                format!("Rate limit exceeded for {}", req.method_name()),
+                None::<()>,
+            );
+
+            return Either::Right(ready(MethodResponse::error(req.id, error)));
+        }
+
+        Either::Left(self.service.call(req))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use super::TokenBucket;
+
+    #[test]
+    fn token_bucket_rejects_requests_over_capacity() {
+        let mut bucket = TokenBucket::new(3);
+
+        assert!(bucket.try_acquire());
+        assert!(bucket.try_acquire());
+        assert!(bucket.try_acquire());
+        assert!(!bucket.try_acquire());
+    }
+
+    #[test]
+    fn token_bucket_refills_over_time() {
+        let mut bucket = TokenBucket::new(2);
+
+        assert!(bucket.try_acquire());
+        assert!(bucket.try_acquire());
+        assert!(!bucket.try_acquire());
+        assert!(!bucket.is_full());
+
+        // Half a second is enough to refill one of the two tokens
+        bucket.last_refill = Instant::now() - Duration::from_millis(500);
+
+        assert!(bucket.try_acquire());
+        assert!(!bucket.try_acquire());
+
+        bucket.last_refill = Instant::now() - Duration::from_secs(10);
+
+        assert!(bucket.is_full());
+        assert!(bucket.try_acquire());
+        assert!(bucket.try_acquire());
+        assert!(!bucket.try_acquire());
+    }
+}
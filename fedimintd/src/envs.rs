@@ -10,6 +10,9 @@ pub const FM_DATA_DIR_ENV: &str = "FM_DATA_DIR";
 // Env variable to TODO
 pub const FM_PASSWORD_ENV: &str = "FM_PASSWORD";
 
+// Env variable to set the new password when changing the password
+pub const FM_NEW_PASSWORD_ENV: &str = "FM_NEW_PASSWORD";
+
 // Env variable to TODO
 pub const FM_TOKIO_CONSOLE_BIND_ENV: &str = "FM_TOKIO_CONSOLE_BIND";
 
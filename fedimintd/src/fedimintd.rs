@@ -5,7 +5,7 @@ use std::net::SocketAddr;
 use std::path::PathBuf;
 use std::time::Duration;
 
-use anyhow::{format_err, Context};
+use anyhow::{bail, format_err, Context};
 use clap::{Parser, Subcommand};
 use fedimint_core::admin_client::ConfigGenParamsRequest;
 use fedimint_core::config::{
@@ -29,7 +29,9 @@ use fedimint_meta_server::{MetaGenParams, MetaInit};
 use fedimint_mint_server::common::config::{MintGenParams, MintGenParamsConsensus};
 use fedimint_mint_server::MintInit;
 use fedimint_server::config::api::ConfigGenSettings;
-use fedimint_server::config::io::{DB_FILE, PLAINTEXT_PASSWORD};
+use fedimint_server::config::io::{
+    change_password, DB_FILE, NEW_PLAINTEXT_PASSWORD, PLAINTEXT_PASSWORD,
+};
 use fedimint_server::config::ServerConfig;
 use fedimint_server::net::api::ApiSecrets;
 use fedimint_unknown_common::config::UnknownGenParams;
@@ -45,16 +47,23 @@ use crate::default_esplora_server;
 use crate::envs::{
     FM_API_URL_ENV, FM_BIND_API_ENV, FM_BIND_METRICS_API_ENV, FM_BIND_P2P_ENV,
     FM_BITCOIN_NETWORK_ENV, FM_DATA_DIR_ENV, FM_DISABLE_META_MODULE_ENV, FM_EXTRA_DKG_META_ENV,
-    FM_FINALITY_DELAY_ENV, FM_FORCE_API_SECRETS_ENV, FM_P2P_URL_ENV, FM_PASSWORD_ENV,
-    FM_TOKIO_CONSOLE_BIND_ENV,
+    FM_FINALITY_DELAY_ENV, FM_FORCE_API_SECRETS_ENV, FM_NEW_PASSWORD_ENV, FM_P2P_URL_ENV,
+    FM_PASSWORD_ENV, FM_TOKIO_CONSOLE_BIND_ENV,
 };
 use crate::fedimintd::metrics::APP_START_TS;
 
 /// Time we will wait before forcefully shutting down tasks
 const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(10);
 
+/// Settings that are only read from the environment
+const SERVER_OPTS_AFTER_HELP: &str = "\
+Transactions submitted through the API are persisted until consensus accepts them:
+  FM_SUBMITTED_TRANSACTION_RESUBMISSION_INTERVAL_SECS  Seconds between resubmissions of pending transactions [default: 60]
+  FM_SUBMITTED_TRANSACTION_TTL_SECS                    Seconds after which a pending transaction is dropped [default: 3600]
+Both have to be positive integers, otherwise fedimintd refuses to start.";
+
 #[derive(Parser)]
-#[command(version)]
+#[command(version, after_help = SERVER_OPTS_AFTER_HELP)]
 pub struct ServerOpts {
     /// Path to folder containing federation config files
     #[arg(long = "data-dir", env = FM_DATA_DIR_ENV)]
@@ -124,6 +133,13 @@ enum ServerSubcommand {
     /// Development-related commands
     #[clap(subcommand)]
     Dev(DevSubcommand),
+    /// Re-encrypt the private config with a new password, which is also used
+    /// to authenticate admin API requests. Fedimintd must not be running.
+    ///
+    /// The new password is read from the FM_NEW_PASSWORD env var or, if that
+    /// is not set, from the first line of stdin, so it doesn't end up in the
+    /// shell history.
+    ChangePassword,
 }
 
 #[derive(Subcommand)]
@@ -175,7 +191,7 @@ fn parse_map(s: &str) -> anyhow::Result<BTreeMap<String, String>> {
 ///     Fedimintd::new(env!("FEDIMINT_BUILD_CODE_VERSION"), Some("vendor-xyz-1"))?
 ///         // use `.with_default_modules()` to avoid having
 ///         // to import these manually
-///         .with_module_kind(WalletInit)
+///         .with_module_kind(WalletInit::default())
 ///         .with_module_kind(MintInit)
 ///         .with_module_kind(LightningInit)
 ///         .run()
@@ -191,6 +207,27 @@ pub struct Fedimintd {
     bitcoind_rpc: BitcoinRpcConfig,
 }
 
+/// Reads the new password for [`ServerSubcommand::ChangePassword`]
+fn read_new_password() -> anyhow::Result<String> {
+    let new_password = match std::env::var(FM_NEW_PASSWORD_ENV) {
+        Ok(new_password) => new_password,
+        Err(_) => {
+            let mut line = String::new();
+            std::io::stdin()
+                .read_line(&mut line)
+                .context("Failed to read the new password from stdin")?;
+
+            line.trim_end_matches(['\r', '\n']).to_owned()
+        }
+    };
+
+    if new_password.is_empty() {
+        bail!("No new password given, set {FM_NEW_PASSWORD_ENV} or pass it via stdin");
+    }
+
+    Ok(new_password)
+}
+
 impl Fedimintd {
     /// Build a new `fedimintd`
     ///
@@ -305,7 +342,7 @@ impl Fedimintd {
                     ),
                 },
             )
-            .with_module_kind(WalletInit)
+            .with_module_kind(WalletInit::default())
             .with_module_instance(
                 WalletInit::kind(),
                 WalletGenParams {
@@ -355,6 +392,39 @@ impl Fedimintd {
         }
     }
 
+    fn change_password(&self, new_password: &str) -> anyhow::Result<()> {
+        let data_dir = self
+            .opts
+            .data_dir
+            .as_ref()
+            .context("data-dir option is not present")?;
+        let password_file = data_dir.join(PLAINTEXT_PASSWORD);
+
+        let password = match &self.opts.password {
+            Some(password) => password.clone(),
+            None => {
+                std::fs::read_to_string(&password_file).context("password option is not present")?
+            }
+        };
+
+        // The plaintext password is used to restart without passing the password. We
+        // write the new one to a temporary file first, so it is already on disk once
+        // the private config is encrypted with it.
+        let new_password_file = data_dir.join(NEW_PLAINTEXT_PASSWORD);
+        let update_password_file = password_file.exists();
+        if update_password_file {
+            write_overwrite(&new_password_file, new_password)?;
+        }
+
+        change_password(data_dir, &password, new_password)?;
+
+        if update_password_file {
+            std::fs::rename(new_password_file, password_file)?;
+        }
+
+        Ok(())
+    }
+
     /// Block thread and run a Fedimintd server
     pub async fn run(self) -> ! {
         // handle optional subcommand
@@ -374,6 +444,16 @@ impl Fedimintd {
                     println!("{db_versions}");
                     std::process::exit(0);
                 }
+                ServerSubcommand::ChangePassword => {
+                    if let Err(error) = read_new_password()
+                        .and_then(|new_password| self.change_password(&new_password))
+                    {
+                        eprintln!("Failed to change password: {error:#}");
+                        std::process::exit(1);
+                    }
+                    println!("Password changed, restart fedimintd with the new password");
+                    std::process::exit(0);
+                }
             }
         }
 
@@ -62,7 +62,11 @@ use fedimint_ln_common::{
 };
 use fedimint_server::config::distributedgen::PeerHandleOps;
 use futures::StreamExt;
-use metrics::{LN_CANCEL_OUTGOING_CONTRACTS, LN_FUNDED_CONTRACT_SATS, LN_INCOMING_OFFER};
+use metrics::{
+    LN_CANCEL_OUTGOING_CONTRACTS, LN_FUNDED_CONTRACT_SATS, LN_GATEWAY_FUNDED_CONTRACT_MSATS_TOTAL,
+    LN_INCOMING_OFFER, LN_LOCKED_CONTRACT_MSATS, LN_OPEN_CONTRACTS,
+    LN_REFUNDED_OUTGOING_CONTRACT_SATS,
+};
 use rand::rngs::OsRng;
 use secp256k1::PublicKey;
 use strum::IntoEnumIterator;
@@ -217,6 +221,7 @@ impl ServerModuleInit for LightningInit {
     async fn init(&self, args: &ServerModuleInitArgs<Self>) -> anyhow::Result<DynServerModule> {
         // Eagerly initialize metrics that trigger infrequently
         LN_CANCEL_OUTGOING_CONTRACTS.get();
+        LN_REFUNDED_OUTGOING_CONTRACT_SATS.get_sample_count();
 
         Ok(Lightning::new(
             args.cfg().to_typed()?,
@@ -566,6 +571,11 @@ impl ServerModule for Lightning {
                     outgoing.gateway_key
                 } else {
                     // otherwise the user can claim the funds back.
+                    let amount = input.amount;
+                    dbtx.on_commit(move || {
+                        LN_REFUNDED_OUTGOING_CONTRACT_SATS.observe(amount.sats_f64());
+                    });
+
                     outgoing.user_key
                 }
             }
@@ -659,6 +669,19 @@ impl ServerModule for Lightning {
                 )
                 .await;
 
+                let (direction, gateway_key) = match &contract.contract {
+                    Contract::Incoming(incoming) => ("incoming", incoming.gateway_key),
+                    Contract::Outgoing(outgoing) => ("outgoing", outgoing.gateway_key),
+                };
+                let gateway_label = gateway_metric_label(dbtx, gateway_key).await;
+                let funded_msats = contract.amount.msats;
+
+                dbtx.on_commit(move || {
+                    LN_GATEWAY_FUNDED_CONTRACT_MSATS_TOTAL
+                        .with_label_values(&[&gateway_label, direction])
+                        .inc_by(funded_msats);
+                });
+
                 if dbtx
                     .insert_entry(&contract_db_key, &updated_contract_account)
                     .await
@@ -814,6 +837,10 @@ impl ServerModule for Lightning {
         audit: &mut Audit,
         module_instance_id: ModuleInstanceId,
     ) {
+        // Audit items are tracked for exactly those funded contracts that still hold
+        // funds, so we derive the open contract metrics from them as well
+        let open_contracts = std::sync::Mutex::new(OpenContracts::default());
+
         audit
             .add_items(
                 dbtx,
@@ -821,9 +848,17 @@ impl ServerModule for Lightning {
                 &LightningAuditItemKeyPrefix,
                 // Both incoming and outgoing contracts represent liabilities to the federation
                 // since they are obligations to issue notes.
-                |_, v| -(v.msats as i64),
+                |k, v| {
+                    open_contracts.lock().expect("poisoned").add(&k, v);
+
+                    -(v.msats as i64)
+                },
             )
             .await;
+
+        let open_contracts = open_contracts.into_inner().expect("poisoned");
+
+        dbtx.on_commit(move || open_contracts.record_metrics());
     }
 
     fn api_endpoints(&self) -> Vec<ApiEndpoint<Self>> {
@@ -1241,6 +1276,61 @@ fn record_funded_contract_metric(updated_contract_account: &ContractAccount) {
         .observe(updated_contract_account.amount.sats_f64());
 }
 
+/// Label of the gateway in [`LN_GATEWAY_FUNDED_CONTRACT_MSATS_TOTAL`]. Anyone
+/// funding a contract can pick its gateway key, so only gateways registered
+/// with us get their own label to bound the number of series.
+async fn gateway_metric_label(
+    dbtx: &mut DatabaseTransaction<'_>,
+    gateway_key: secp256k1::PublicKey,
+) -> String {
+    if dbtx
+        .get_value(&LightningGatewayKey(gateway_key))
+        .await
+        .is_some()
+    {
+        gateway_key.to_string()
+    } else {
+        "unregistered".to_string()
+    }
+}
+
+/// Number and amount of funded contracts that still hold funds per direction
+#[derive(Debug, Default)]
+struct OpenContracts {
+    incoming_count: i64,
+    incoming_msats: i64,
+    outgoing_count: i64,
+    outgoing_msats: i64,
+}
+
+impl OpenContracts {
+    fn add(&mut self, key: &LightningAuditItemKey, amount: Amount) {
+        let (count, msats) = match key {
+            LightningAuditItemKey::Incoming(_) => {
+                (&mut self.incoming_count, &mut self.incoming_msats)
+            }
+            LightningAuditItemKey::Outgoing(_) => {
+                (&mut self.outgoing_count, &mut self.outgoing_msats)
+            }
+        };
+
+        *count += 1;
+        *msats += amount.msats as i64;
+    }
+
+    fn record_metrics(&self) {
+        for (direction, count, msats) in [
+            ("incoming", self.incoming_count, self.incoming_msats),
+            ("outgoing", self.outgoing_count, self.outgoing_msats),
+        ] {
+            LN_OPEN_CONTRACTS.with_label_values(&[direction]).set(count);
+            LN_LOCKED_CONTRACT_MSATS
+                .with_label_values(&[direction])
+                .set(msats);
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use assert_matches::assert_matches;
@@ -1,8 +1,11 @@
 use fedimint_metrics::prometheus::{
-    register_histogram_vec_with_registry, register_int_counter_with_registry,
+    register_histogram_vec_with_registry, register_histogram_with_registry,
+    register_int_counter_vec_with_registry, register_int_counter_with_registry,
+    register_int_gauge_vec_with_registry, IntGaugeVec,
 };
 use fedimint_metrics::{
-    histogram_opts, opts, HistogramVec, IntCounter, AMOUNTS_BUCKETS_SATS, REGISTRY,
+    histogram_opts, opts, Histogram, HistogramVec, IntCounter, IntCounterVec, AMOUNTS_BUCKETS_SATS,
+    REGISTRY,
 };
 use once_cell::sync::Lazy;
 
@@ -35,3 +38,47 @@ pub static LN_FUNDED_CONTRACT_SATS: Lazy<HistogramVec> = Lazy::new(|| {
     )
     .unwrap()
 });
+pub static LN_REFUNDED_OUTGOING_CONTRACT_SATS: Lazy<Histogram> = Lazy::new(|| {
+    register_histogram_with_registry!(
+        histogram_opts!(
+            "ln_refunded_outgoing_contract_sats",
+            "Amount in sats refunded to the payer of a timed out or canceled outgoing contract",
+            AMOUNTS_BUCKETS_SATS.clone()
+        ),
+        REGISTRY
+    )
+    .unwrap()
+});
+pub static LN_OPEN_CONTRACTS: Lazy<IntGaugeVec> = Lazy::new(|| {
+    register_int_gauge_vec_with_registry!(
+        opts!(
+            "ln_open_contracts",
+            "Number of funded contracts (with outgoing or incoming direction) that still hold funds"
+        ),
+        &["direction"],
+        REGISTRY
+    )
+    .unwrap()
+});
+pub static LN_LOCKED_CONTRACT_MSATS: Lazy<IntGaugeVec> = Lazy::new(|| {
+    register_int_gauge_vec_with_registry!(
+        opts!(
+            "ln_locked_contract_msats",
+            "Total amount in msats locked in open contracts (with outgoing or incoming direction)"
+        ),
+        &["direction"],
+        REGISTRY
+    )
+    .unwrap()
+});
+pub static LN_GATEWAY_FUNDED_CONTRACT_MSATS_TOTAL: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec_with_registry!(
+        opts!(
+            "ln_gateway_funded_contract_msats_total",
+            "Amount in msats funded into contracts (with outgoing or incoming direction) by registered gateway"
+        ),
+        &["gateway_key", "direction"],
+        REGISTRY
+    )
+    .unwrap()
+});
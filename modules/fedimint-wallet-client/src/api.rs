@@ -1,10 +1,13 @@
 use bitcoin::Address;
 use fedimint_api_client::api::{FederationApiExt, FederationResult, IModuleFederationApi};
-use fedimint_core::module::ApiRequestErased;
+use fedimint_core::module::{ApiAuth, ApiRequestErased};
 use fedimint_core::task::{MaybeSend, MaybeSync};
 use fedimint_core::{apply, async_trait_maybe_send};
-use fedimint_wallet_common::endpoint_constants::{BLOCK_COUNT_ENDPOINT, PEG_OUT_FEES_ENDPOINT};
-use fedimint_wallet_common::PegOutFees;
+use fedimint_wallet_common::endpoint_constants::{
+    APPROVE_PEG_OUT_ENDPOINT, BLOCK_COUNT_ENDPOINT, PEG_OUT_FEES_ENDPOINT,
+    PENDING_PEG_OUT_APPROVALS_ENDPOINT, WALLET_SUMMARY_ENDPOINT, WATCH_ONLY_DESCRIPTORS_ENDPOINT,
+};
+use fedimint_wallet_common::{PegOutFees, TxOutputSummary, WalletSummary};
 
 #[apply(async_trait_maybe_send!)]
 pub trait WalletFederationApi {
@@ -14,6 +17,13 @@ pub trait WalletFederationApi {
         address: &Address,
         amount: bitcoin::Amount,
     ) -> FederationResult<Option<PegOutFees>>;
+    async fn fetch_wallet_summary(&self) -> FederationResult<WalletSummary>;
+    async fn fetch_pending_peg_out_approvals(
+        &self,
+        auth: ApiAuth,
+    ) -> FederationResult<Vec<TxOutputSummary>>;
+    async fn approve_peg_out(&self, auth: ApiAuth, txid: bitcoin::Txid) -> FederationResult<bool>;
+    async fn fetch_watch_only_descriptors(&self, auth: ApiAuth) -> FederationResult<Vec<String>>;
 }
 
 #[apply(async_trait_maybe_send!)]
@@ -40,4 +50,41 @@ where
         )
         .await
     }
+
+    async fn fetch_wallet_summary(&self) -> FederationResult<WalletSummary> {
+        self.request_current_consensus(
+            WALLET_SUMMARY_ENDPOINT.to_string(),
+            ApiRequestErased::default(),
+        )
+        .await
+    }
+
+    async fn fetch_pending_peg_out_approvals(
+        &self,
+        auth: ApiAuth,
+    ) -> FederationResult<Vec<TxOutputSummary>> {
+        self.request_admin(
+            PENDING_PEG_OUT_APPROVALS_ENDPOINT,
+            ApiRequestErased::default(),
+            auth,
+        )
+        .await
+    }
+
+    async fn approve_peg_out(&self, auth: ApiAuth, txid: bitcoin::Txid) -> FederationResult<bool> {
+        let peg_out_existed: bool = self
+            .request_admin(APPROVE_PEG_OUT_ENDPOINT, ApiRequestErased::new(txid), auth)
+            .await?;
+
+        Ok(peg_out_existed)
+    }
+
+    async fn fetch_watch_only_descriptors(&self, auth: ApiAuth) -> FederationResult<Vec<String>> {
+        self.request_admin(
+            WATCH_ONLY_DESCRIPTORS_ENDPOINT,
+            ApiRequestErased::default(),
+            auth,
+        )
+        .await
+    }
 }
@@ -1,3 +1,7 @@
 pub const BLOCK_COUNT_ENDPOINT: &str = "block_count";
 pub const PEG_OUT_FEES_ENDPOINT: &str = "peg_out_fees";
 pub const BLOCK_COUNT_LOCAL_ENDPOINT: &str = "block_count_local";
+pub const WALLET_SUMMARY_ENDPOINT: &str = "wallet_summary";
+pub const PENDING_PEG_OUT_APPROVALS_ENDPOINT: &str = "pending_peg_out_approvals";
+pub const APPROVE_PEG_OUT_ENDPOINT: &str = "approve_peg_out";
+pub const WATCH_ONLY_DESCRIPTORS_ENDPOINT: &str = "watch_only_descriptors";
@@ -1,2 +1,6 @@
 // Env variable to TODO
 pub const FM_PORT_ESPLORA_ENV: &str = "FM_PORT_ESPLORA";
+
+/// Env variable for the peg-out amount in sats above which our signature is
+/// only contributed after the operator approved the peg-out
+pub const FM_PEG_OUT_APPROVAL_THRESHOLD_SATS_ENV: &str = "FM_PEG_OUT_APPROVAL_THRESHOLD_SATS";
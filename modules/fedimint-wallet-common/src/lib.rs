@@ -91,6 +91,54 @@ pub struct SpendableUTXO {
     pub amount: bitcoin::Amount,
 }
 
+/// A bitcoin output owned by or paid out from the federation wallet
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize, Encodable, Decodable)]
+pub struct TxOutputSummary {
+    pub outpoint: bitcoin::OutPoint,
+    #[serde(with = "bitcoin::amount::serde::as_sat")]
+    pub amount: bitcoin::Amount,
+}
+
+/// Summary of the federation's on-chain funds, including peg-outs that have
+/// been accepted by consensus but are not yet confirmed on-chain
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize, Encodable, Decodable)]
+pub struct WalletSummary {
+    /// UTXOs the federation can spend
+    pub spendable_utxos: Vec<TxOutputSummary>,
+    /// Peg-out outputs of transactions still collecting a threshold of
+    /// signatures
+    pub unsigned_peg_out_txos: Vec<TxOutputSummary>,
+    /// Peg-out outputs of fully signed transactions that are broadcast
+    /// periodically until they confirm
+    pub unconfirmed_peg_out_txos: Vec<TxOutputSummary>,
+}
+
+impl WalletSummary {
+    fn sum<'a>(txos: impl Iterator<Item = &'a TxOutputSummary>) -> Amount {
+        txos.fold(Amount::ZERO, |acc, txo| acc + txo.amount)
+    }
+
+    /// Total amount the federation can spend
+    pub fn total_spendable_balance(&self) -> Amount {
+        WalletSummary::sum(self.spendable_utxos.iter())
+    }
+
+    /// Total amount of peg-outs still collecting signatures
+    pub fn total_unsigned_peg_out_balance(&self) -> Amount {
+        WalletSummary::sum(self.unsigned_peg_out_txos.iter())
+    }
+
+    /// Total amount of signed peg-outs awaiting confirmation
+    pub fn total_unconfirmed_peg_out_balance(&self) -> Amount {
+        WalletSummary::sum(self.unconfirmed_peg_out_txos.iter())
+    }
+
+    /// Total amount of peg-outs that have not confirmed yet
+    pub fn total_pending_peg_out_balance(&self) -> Amount {
+        self.total_unsigned_peg_out_balance() + self.total_unconfirmed_peg_out_balance()
+    }
+}
+
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Deserialize, Serialize, Encodable, Decodable)]
 pub struct PegOutFees {
     pub fee_rate: Feerate,
@@ -338,8 +386,25 @@ pub enum ProcessPegOutSigError {
     InvalidSignature,
     #[error("Duplicate signature")]
     DuplicateSignature,
+    #[error("Signature conflicts with a previous signature by the same peer")]
+    ConflictingSignature,
     #[error("Missing change tweak")]
     MissingOrMalformedChangeTweak,
     #[error("Error finalizing PSBT {0:?}")]
     ErrorFinalizingPsbt(Vec<miniscript::psbt::Error>),
 }
+
+impl ProcessPegOutSigError {
+    /// Returns true if the error can only be caused by a peer that deviates
+    /// from the protocol, as opposed to a benign retransmission of a signature
+    /// we already processed
+    pub fn is_peer_misbehavior(&self) -> bool {
+        matches!(
+            self,
+            ProcessPegOutSigError::WrongSignatureCount(..)
+                | ProcessPegOutSigError::MalformedSignature(..)
+                | ProcessPegOutSigError::InvalidSignature
+                | ProcessPegOutSigError::ConflictingSignature
+        )
+    }
+}
@@ -1,7 +1,6 @@
 use std::convert::Infallible;
 use std::hash::Hash;
 
-use anyhow::format_err;
 use bitcoin::{BlockHash, OutPoint, Transaction};
 use fedimint_core::encoding::{Decodable, DecodeError, Encodable};
 use fedimint_core::module::registry::ModuleDecoderRegistry;
@@ -64,28 +63,16 @@ impl PegInProof {
         output_idx: u32,
         tweak_contract_key: secp256k1::PublicKey,
     ) -> Result<PegInProof, PegInProofError> {
-        // TODO: remove redundancy with serde validation
-        if !txout_proof.contains_tx(transaction.txid()) {
-            return Err(PegInProofError::TransactionNotInProof);
-        }
-
-        if transaction.output.len() > u32::MAX as usize {
-            return Err(PegInProofError::TooManyTransactionOutputs);
-        }
-
-        if transaction.output.get(output_idx as usize).is_none() {
-            return Err(PegInProofError::OutputIndexOutOfRange(
-                u64::from(output_idx),
-                transaction.output.len() as u64,
-            ));
-        }
-
-        Ok(PegInProof {
+        let proof = PegInProof {
             txout_proof,
             transaction,
             output_idx,
             tweak_contract_key,
-        })
+        };
+
+        validate_peg_in_proof(&proof)?;
+
+        Ok(proof)
     }
 
     pub fn verify<C: Verification + Signing>(
@@ -169,23 +156,28 @@ impl Tweakable for Descriptor<CompressedPublicKey> {
     }
 }
 
-fn validate_peg_in_proof(proof: &PegInProof) -> Result<(), anyhow::Error> {
+/// Checks the invariants of a [`PegInProof`] that do not depend on the
+/// federation's state, so that every constructor and decoder enforces them
+fn validate_peg_in_proof(proof: &PegInProof) -> Result<(), PegInProofError> {
     if !proof.txout_proof.contains_tx(proof.transaction.txid()) {
-        return Err(format_err!("Supplied transaction is not included in proof",));
+        return Err(PegInProofError::TransactionNotInProof);
     }
 
     if proof.transaction.output.len() > u32::MAX as usize {
-        return Err(format_err!("Supplied transaction has too many outputs",));
+        return Err(PegInProofError::TooManyTransactionOutputs);
     }
 
     match proof.transaction.output.get(proof.output_idx as usize) {
         Some(txo) => {
-            if txo.value > 2_100_000_000_000_000 {
-                return Err(format_err!("Txout amount out of range"));
+            if txo.value > bitcoin::Amount::MAX_MONEY.to_sat() {
+                return Err(PegInProofError::TxOutAmountOutOfRange(txo.value));
             }
         }
         None => {
-            return Err(format_err!("Output index out of range"));
+            return Err(PegInProofError::OutputIndexOutOfRange(
+                u64::from(proof.output_idx),
+                proof.transaction.output.len() as u64,
+            ));
         }
     }
 
@@ -204,7 +196,7 @@ impl Decodable for PegInProof {
             tweak_contract_key: secp256k1::PublicKey::consensus_decode(d, modules)?,
         };
 
-        validate_peg_in_proof(&slf).map_err(DecodeError::new_custom)?;
+        validate_peg_in_proof(&slf).map_err(DecodeError::from_err)?;
         Ok(slf)
     }
 }
@@ -219,16 +211,27 @@ pub enum PegInProofError {
     OutputIndexOutOfRange(u64, u64),
     #[error("The expected script given the tweak did not match the actual script")]
     ScriptDoesNotMatch,
+    #[error("The amount of the referenced output is out of range: {0} sats")]
+    TxOutAmountOutOfRange(u64),
 }
 
 #[cfg(test)]
 mod tests {
     use std::io::Cursor;
 
+    use bitcoin::absolute::LockTime;
+    use bitcoin::block::{Header as BlockHeader, Version};
+    use bitcoin::hash_types::TxMerkleNode;
+    use bitcoin::hashes::Hash;
+    use bitcoin::merkle_tree::PartialMerkleTree;
+    use bitcoin::{BlockHash, CompactTarget, Transaction, TxOut};
     use fedimint_core::encoding::Decodable;
     use fedimint_core::module::registry::ModuleDecoderRegistry;
     use fedimint_core::txoproof::TxOutProof;
     use hex::FromHex;
+    use secp256k1::SECP256K1;
+
+    use super::{PegInProof, PegInProofError};
 
     #[test_log::test]
     fn test_txoutproof_happy_path() {
@@ -265,4 +268,62 @@ mod tests {
                 .unwrap()
         ));
     }
+
+    fn transaction_with_output_value(value: u64) -> Transaction {
+        Transaction {
+            version: 2,
+            lock_time: LockTime::ZERO,
+            input: vec![],
+            output: vec![TxOut {
+                value,
+                script_pubkey: Default::default(),
+            }],
+        }
+    }
+
+    /// Creates a proof for a block that only contains `transaction`
+    fn txoutproof_for(transaction: &Transaction) -> TxOutProof {
+        let merkle_proof = PartialMerkleTree::from_txids(&[transaction.txid()], &[true]);
+
+        TxOutProof {
+            block_header: BlockHeader {
+                version: Version::TWO,
+                prev_blockhash: BlockHash::all_zeros(),
+                merkle_root: TxMerkleNode::from_raw_hash(transaction.txid().to_raw_hash()),
+                time: 0,
+                bits: CompactTarget::from_consensus(0),
+                nonce: 0,
+            },
+            merkle_proof,
+        }
+    }
+
+    #[test]
+    fn test_peg_in_proof_rejects_transaction_not_in_proof() {
+        let transaction = transaction_with_output_value(1000);
+        let other_transaction = transaction_with_output_value(2000);
+        let (_, tweak_key) = SECP256K1.generate_keypair(&mut secp256k1::rand::thread_rng());
+
+        assert_eq!(
+            PegInProof::new(
+                txoutproof_for(&other_transaction),
+                transaction,
+                0,
+                tweak_key
+            ),
+            Err(PegInProofError::TransactionNotInProof)
+        );
+    }
+
+    #[test]
+    fn test_peg_in_proof_rejects_output_amount_out_of_range() {
+        let value = bitcoin::Amount::MAX_MONEY.to_sat() + 1;
+        let transaction = transaction_with_output_value(value);
+        let (_, tweak_key) = SECP256K1.generate_keypair(&mut secp256k1::rand::thread_rng());
+
+        assert_eq!(
+            PegInProof::new(txoutproof_for(&transaction), transaction, 0, tweak_key),
+            Err(PegInProofError::TxOutAmountOutOfRange(value))
+        );
+    }
 }
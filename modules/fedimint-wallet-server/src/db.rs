@@ -19,6 +19,7 @@ pub enum DbKeyPrefix {
     PegOutTxSigCi = 0x36,
     PegOutBitcoinOutPoint = 0x37,
     PegOutNonce = 0x38,
+    PegOutApproval = 0x39,
 }
 
 impl std::fmt::Display for DbKeyPrefix {
@@ -154,3 +155,18 @@ impl_db_record!(
     value = u64,
     db_prefix = DbKeyPrefix::PegOutNonce
 );
+
+/// Marks a peg-out above the approval threshold as approved by our operator.
+/// This is local to our guardian and not part of consensus.
+#[derive(Clone, Debug, Encodable, Decodable, Serialize)]
+pub struct PegOutApprovalKey(pub Txid);
+
+#[derive(Clone, Debug, Encodable, Decodable)]
+pub struct PegOutApprovalPrefix;
+
+impl_db_record!(
+    key = PegOutApprovalKey,
+    value = (),
+    db_prefix = DbKeyPrefix::PegOutApproval,
+);
+impl_db_lookup!(key = PegOutApprovalKey, query_prefix = PegOutApprovalPrefix);
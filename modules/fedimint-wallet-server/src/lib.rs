@@ -29,8 +29,9 @@ use bitcoin::{Address, BlockHash, Network, ScriptBuf, Sequence, Transaction, TxI
 use common::config::WalletConfigConsensus;
 use common::{
     proprietary_tweak_key, PegOutFees, PegOutSignatureItem, ProcessPegOutSigError, SpendableUTXO,
-    WalletCommonInit, WalletConsensusItem, WalletCreationError, WalletInput, WalletModuleTypes,
-    WalletOutput, WalletOutputOutcome, CONFIRMATION_TARGET, DEPRECATED_RBF_ERROR,
+    TxOutputSummary, WalletCommonInit, WalletConsensusItem, WalletCreationError, WalletInput,
+    WalletModuleTypes, WalletOutput, WalletOutputOutcome, WalletSummary, CONFIRMATION_TARGET,
+    DEPRECATED_RBF_ERROR,
 };
 use fedimint_bitcoind::{create_bitcoind, DynBitcoindRpc};
 use fedimint_core::config::{
@@ -61,11 +62,15 @@ use fedimint_core::{
 };
 use fedimint_logging::LOG_MODULE_WALLET;
 use fedimint_server::config::distributedgen::PeerHandleOps;
+use fedimint_server::net::api::check_auth;
 pub use fedimint_wallet_common as common;
 use fedimint_wallet_common::config::{WalletClientConfig, WalletConfig, WalletGenParams};
 use fedimint_wallet_common::endpoint_constants::{
-    BLOCK_COUNT_ENDPOINT, BLOCK_COUNT_LOCAL_ENDPOINT, PEG_OUT_FEES_ENDPOINT,
+    APPROVE_PEG_OUT_ENDPOINT, BLOCK_COUNT_ENDPOINT, BLOCK_COUNT_LOCAL_ENDPOINT,
+    PEG_OUT_FEES_ENDPOINT, PENDING_PEG_OUT_APPROVALS_ENDPOINT, WALLET_SUMMARY_ENDPOINT,
+    WATCH_ONLY_DESCRIPTORS_ENDPOINT,
 };
+use fedimint_wallet_common::envs::FM_PEG_OUT_APPROVAL_THRESHOLD_SATS_ENV;
 use fedimint_wallet_common::keys::CompressedPublicKey;
 use fedimint_wallet_common::tweakable::Tweakable;
 use fedimint_wallet_common::{
@@ -88,17 +93,49 @@ use tracing::{debug, info, instrument, trace, warn};
 
 use crate::db::{
     BlockCountVoteKey, BlockCountVotePrefix, BlockHashKey, BlockHashKeyPrefix, DbKeyPrefix,
-    FeeRateVoteKey, FeeRateVotePrefix, PegOutBitcoinTransaction, PegOutBitcoinTransactionPrefix,
-    PegOutNonceKey, PegOutTxSignatureCI, PegOutTxSignatureCIPrefix, PendingTransactionKey,
-    PendingTransactionPrefixKey, UTXOKey, UTXOPrefixKey, UnsignedTransactionKey,
-    UnsignedTransactionPrefixKey,
+    FeeRateVoteKey, FeeRateVotePrefix, PegOutApprovalKey, PegOutApprovalPrefix,
+    PegOutBitcoinTransaction, PegOutBitcoinTransactionPrefix, PegOutNonceKey, PegOutTxSignatureCI,
+    PegOutTxSignatureCIPrefix, PendingTransactionKey, PendingTransactionPrefixKey, UTXOKey,
+    UTXOPrefixKey, UnsignedTransactionKey, UnsignedTransactionPrefixKey,
 };
-use crate::metrics::WALLET_BLOCK_COUNT;
+use crate::metrics::{WALLET_BLOCK_COUNT, WALLET_PEG_OUT_FAULTY_SIGNATURES_TOTAL};
 
 mod metrics;
 
-#[derive(Debug, Clone)]
-pub struct WalletInit;
+/// How often we remove the approvals of peg-outs that were signed already
+const PEG_OUT_APPROVAL_CLEANUP_INTERVAL: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone, Default)]
+pub struct WalletInit {
+    /// Takes precedence over [`FM_PEG_OUT_APPROVAL_THRESHOLD_SATS_ENV`]
+    peg_out_approval_threshold: Option<bitcoin::Amount>,
+}
+
+impl WalletInit {
+    /// Require operator approval for peg-outs above `threshold` regardless of
+    /// the environment
+    pub fn with_peg_out_approval_threshold(threshold: bitcoin::Amount) -> Self {
+        Self {
+            peg_out_approval_threshold: Some(threshold),
+        }
+    }
+
+    fn peg_out_approval_threshold(&self) -> anyhow::Result<Option<bitcoin::Amount>> {
+        if let Some(threshold) = self.peg_out_approval_threshold {
+            return Ok(Some(threshold));
+        }
+
+        let Ok(threshold) = std::env::var(FM_PEG_OUT_APPROVAL_THRESHOLD_SATS_ENV) else {
+            return Ok(None);
+        };
+
+        let threshold = threshold.parse::<u64>().with_context(|| {
+            format!("Invalid {FM_PEG_OUT_APPROVAL_THRESHOLD_SATS_ENV}: {threshold}")
+        })?;
+
+        Ok(Some(bitcoin::Amount::from_sat(threshold)))
+    }
+}
 
 impl ModuleInit for WalletInit {
     type Common = WalletCommonInit;
@@ -195,6 +232,16 @@ impl ModuleInit for WalletInit {
                         "Fee Rate Votes"
                     );
                 }
+
+                DbKeyPrefix::PegOutApproval => {
+                    push_db_key_items!(
+                        dbtx,
+                        PegOutApprovalPrefix,
+                        PegOutApprovalKey,
+                        wallet,
+                        "Peg Out Approvals"
+                    );
+                }
             }
         }
 
@@ -217,7 +264,7 @@ impl ServerModuleInit for WalletInit {
                 MODULE_CONSENSUS_VERSION.major,
                 MODULE_CONSENSUS_VERSION.minor,
             ),
-            &[(0, 0)],
+            &[(0, 3)],
         )
     }
 
@@ -236,11 +283,14 @@ impl ServerModuleInit for WalletInit {
         WALLET_PEGOUT_SATS.get_sample_count();
         WALLET_PEGOUT_FEES_SATS.get_sample_count();
 
+        let peg_out_approval_threshold = self.peg_out_approval_threshold()?;
+
         Ok(Wallet::new(
             args.cfg().to_typed()?,
             args.db(),
             &mut args.task_group().clone(),
             args.our_peer_id(),
+            peg_out_approval_threshold,
         )
         .await?
         .into())
@@ -356,18 +406,28 @@ impl ServerModule for Wallet {
         &'a self,
         dbtx: &mut DatabaseTransaction<'_>,
     ) -> Vec<WalletConsensusItem> {
-        let mut items = dbtx
+        let peg_out_signatures = dbtx
             .find_by_prefix(&PegOutTxSignatureCIPrefix)
             .await
-            .map(|(key, val)| {
-                WalletConsensusItem::PegOutSignature(PegOutSignatureItem {
-                    txid: key.0,
-                    signature: val,
-                })
-            })
-            .collect::<Vec<WalletConsensusItem>>()
+            .map(|(key, val)| (key.0, val))
+            .collect::<Vec<_>>()
             .await;
 
+        let mut items = Vec::new();
+
+        for (txid, signature) in peg_out_signatures {
+            if self.requires_approval(dbtx, txid).await {
+                debug!(target: LOG_MODULE_WALLET, %txid, "Withholding peg-out signature until approved");
+
+                continue;
+            }
+
+            items.push(WalletConsensusItem::PegOutSignature(PegOutSignatureItem {
+                txid,
+                signature,
+            }));
+        }
+
         // If we are unable to get a block count from the node we skip adding a block
         // count vote to consensus items.
         //
@@ -478,6 +538,7 @@ impl ServerModule for Wallet {
                     .context("Unsigned transaction does not exist")?;
 
                 self.sign_peg_out_psbt(&mut unsigned.psbt, peer, &peg_out_signature)
+                    .inspect_err(|error| report_peg_out_signature_error(peer, txid, error))
                     .context("Peg out signature is invalid")?;
 
                 dbtx.insert_entry(&UnsignedTransactionKey(txid), &unsigned)
@@ -720,6 +781,40 @@ impl ServerModule for Wallet {
                     }
                 }
             },
+            api_endpoint! {
+                WALLET_SUMMARY_ENDPOINT,
+                ApiVersion::new(0, 1),
+                async |module: &Wallet, context, _params: ()| -> WalletSummary {
+                    Ok(module.get_wallet_summary(&mut context.dbtx().into_nc()).await)
+                }
+            },
+            api_endpoint! {
+                PENDING_PEG_OUT_APPROVALS_ENDPOINT,
+                ApiVersion::new(0, 2),
+                async |module: &Wallet, context, _params: ()| -> Vec<TxOutputSummary> {
+                    check_auth(context)?;
+
+                    Ok(module.pending_peg_out_approvals(&mut context.dbtx().into_nc()).await)
+                }
+            },
+            api_endpoint! {
+                APPROVE_PEG_OUT_ENDPOINT,
+                ApiVersion::new(0, 2),
+                async |module: &Wallet, context, txid: Txid| -> bool {
+                    check_auth(context)?;
+
+                    Ok(module.approve_peg_out(&mut context.dbtx().into_nc(), txid).await)
+                }
+            },
+            api_endpoint! {
+                WATCH_ONLY_DESCRIPTORS_ENDPOINT,
+                ApiVersion::new(0, 3),
+                async |module: &Wallet, context, _params: ()| -> Vec<String> {
+                    check_auth(context)?;
+
+                    Ok(module.watch_only_descriptors(&mut context.dbtx().into_nc()).await)
+                }
+            },
         ]
     }
 }
@@ -765,6 +860,9 @@ pub struct Wallet {
     btc_rpc: DynBitcoindRpc,
     /// The result of last successful get_block_count
     block_count_local: std::sync::Mutex<Option<u32>>,
+    /// Peg-outs above this amount need to be approved by our operator before
+    /// we contribute our signature
+    peg_out_approval_threshold: Option<bitcoin::Amount>,
     our_peer_id: PeerId,
     /// Block count updated periodically by a background task
     block_count_rx: watch::Receiver<Option<u32>>,
@@ -778,9 +876,18 @@ impl Wallet {
         db: &Database,
         task_group: &mut TaskGroup,
         our_peer_id: PeerId,
+        peg_out_approval_threshold: Option<bitcoin::Amount>,
     ) -> anyhow::Result<Wallet> {
         let btc_rpc = create_bitcoind(&cfg.local.bitcoin_rpc, task_group.make_handle())?;
-        Ok(Self::new_with_bitcoind(cfg, db, btc_rpc, task_group, our_peer_id).await?)
+        Ok(Self::new_with_bitcoind(
+            cfg,
+            db,
+            btc_rpc,
+            task_group,
+            our_peer_id,
+            peg_out_approval_threshold,
+        )
+        .await?)
     }
 
     pub async fn new_with_bitcoind(
@@ -789,9 +896,12 @@ impl Wallet {
         bitcoind: DynBitcoindRpc,
         task_group: &mut TaskGroup,
         our_peer_id: PeerId,
+        peg_out_approval_threshold: Option<bitcoin::Amount>,
     ) -> Result<Wallet, WalletCreationError> {
         Self::spawn_broadcast_pending_task(task_group, &bitcoind, db);
 
+        Self::spawn_peg_out_approval_cleanup_task(task_group, db);
+
         let (block_count_rx, fee_rate_rx) =
             Self::spawn_bitcoin_update_task(&cfg, task_group, &bitcoind);
 
@@ -812,6 +922,7 @@ impl Wallet {
             cfg,
             secp: Default::default(),
             block_count_local: Default::default(),
+            peg_out_approval_threshold,
             btc_rpc: bitcoind_rpc,
             our_peer_id,
             block_count_rx,
@@ -835,56 +946,7 @@ impl Wallet {
             .get(&peer)
             .expect("always called with valid peer id");
 
-        if psbt.inputs.len() != signature.signature.len() {
-            return Err(ProcessPegOutSigError::WrongSignatureCount(
-                psbt.inputs.len(),
-                signature.signature.len(),
-            ));
-        }
-
-        let mut tx_hasher = SighashCache::new(&psbt.unsigned_tx);
-        for (idx, (input, signature)) in psbt
-            .inputs
-            .iter_mut()
-            .zip(signature.signature.iter())
-            .enumerate()
-        {
-            let tx_hash = tx_hasher
-                .segwit_signature_hash(
-                    idx,
-                    input
-                        .witness_script
-                        .as_ref()
-                        .expect("Missing witness script"),
-                    input.witness_utxo.as_ref().expect("Missing UTXO").value,
-                    EcdsaSighashType::All,
-                )
-                .map_err(|_| ProcessPegOutSigError::SighashError)?;
-
-            let tweak = input
-                .proprietary
-                .get(&proprietary_tweak_key())
-                .expect("we saved it with a tweak");
-
-            let tweaked_peer_key = peer_key.tweak(tweak, &self.secp);
-            self.secp
-                .verify_ecdsa(
-                    &Message::from_slice(&tx_hash[..]).unwrap(),
-                    signature,
-                    &tweaked_peer_key.key,
-                )
-                .map_err(|_| ProcessPegOutSigError::InvalidSignature)?;
-
-            if input
-                .partial_sigs
-                .insert(tweaked_peer_key.into(), EcdsaSig::sighash_all(*signature))
-                .is_some()
-            {
-                // Should never happen since peers only sign a PSBT once
-                return Err(ProcessPegOutSigError::DuplicateSignature);
-            }
-        }
-        Ok(())
+        attach_peg_out_signature(&self.secp, peer_key, psbt, signature)
     }
 
     fn finalize_peg_out_psbt(
@@ -1191,6 +1253,160 @@ impl Wallet {
         bitcoin::Amount::from_sat(sat_sum)
     }
 
+    /// Summarizes the spendable UTXOs of the federation as well as all
+    /// peg-outs that are still awaiting signatures or confirmation
+    pub async fn get_wallet_summary(&self, dbtx: &mut DatabaseTransaction<'_>) -> WalletSummary {
+        let spendable_utxos = self
+            .available_utxos(dbtx)
+            .await
+            .into_iter()
+            .map(|(utxo_key, utxo)| TxOutputSummary {
+                outpoint: utxo_key.0,
+                amount: utxo.amount,
+            })
+            .collect();
+
+        let unsigned_peg_out_txos = dbtx
+            .find_by_prefix(&UnsignedTransactionPrefixKey)
+            .await
+            .filter_map(|(_, tx)| async move {
+                peg_out_txo(&tx.psbt.unsigned_tx, &tx.destination, tx.peg_out_amount)
+            })
+            .collect()
+            .await;
+
+        let unconfirmed_peg_out_txos = dbtx
+            .find_by_prefix(&PendingTransactionPrefixKey)
+            .await
+            .filter_map(
+                |(_, tx)| async move { peg_out_txo(&tx.tx, &tx.destination, tx.peg_out_amount) },
+            )
+            .collect()
+            .await;
+
+        WalletSummary {
+            spendable_utxos,
+            unsigned_peg_out_txos,
+            unconfirmed_peg_out_txos,
+        }
+    }
+
+    /// Returns true if the peg-out exceeds our approval threshold and was not
+    /// yet approved by our operator
+    async fn requires_approval(&self, dbtx: &mut DatabaseTransaction<'_>, txid: Txid) -> bool {
+        let Some(threshold) = self.peg_out_approval_threshold else {
+            return false;
+        };
+
+        let Some(unsigned) = dbtx.get_value(&UnsignedTransactionKey(txid)).await else {
+            return false;
+        };
+
+        threshold < unsigned.peg_out_amount
+            && dbtx.get_value(&PegOutApprovalKey(txid)).await.is_none()
+    }
+
+    /// Lists the unsigned peg-outs we withhold our signature for until they are
+    /// approved by our operator
+    pub async fn pending_peg_out_approvals(
+        &self,
+        dbtx: &mut DatabaseTransaction<'_>,
+    ) -> Vec<TxOutputSummary> {
+        let unsigned_txids = dbtx
+            .find_by_prefix(&UnsignedTransactionPrefixKey)
+            .await
+            .map(|(key, _)| key.0)
+            .collect::<Vec<_>>()
+            .await;
+
+        let mut pending = Vec::new();
+
+        for txid in unsigned_txids {
+            if self.requires_approval(dbtx, txid).await {
+                let unsigned = dbtx
+                    .get_value(&UnsignedTransactionKey(txid))
+                    .await
+                    .expect("Checked above");
+
+                pending.extend(peg_out_txo(
+                    &unsigned.psbt.unsigned_tx,
+                    &unsigned.destination,
+                    unsigned.peg_out_amount,
+                ));
+            }
+        }
+
+        pending
+    }
+
+    /// Approves an unsigned peg-out, returns false if there is no such peg-out
+    pub async fn approve_peg_out(&self, dbtx: &mut DatabaseTransaction<'_>, txid: Txid) -> bool {
+        if dbtx
+            .get_value(&UnsignedTransactionKey(txid))
+            .await
+            .is_none()
+        {
+            return false;
+        }
+
+        info!(target: LOG_MODULE_WALLET, %txid, "Peg-out approved by operator");
+
+        dbtx.insert_entry(&PegOutApprovalKey(txid), &()).await;
+
+        true
+    }
+
+    /// Exports a watch-only descriptor for every output the federation
+    /// currently owns or is about to receive as change, including the inputs
+    /// of peg-outs that are not confirmed yet. The descriptors only contain
+    /// public keys and can be imported into Bitcoin Core or Sparrow to
+    /// independently monitor the federation's funds.
+    ///
+    /// Every peg-in is tweaked individually, so the descriptors of UTXOs the
+    /// federation spent before the export are not known anymore and their
+    /// history has to be found by rescanning from the exported ones.
+    pub async fn watch_only_descriptors(&self, dbtx: &mut DatabaseTransaction<'_>) -> Vec<String> {
+        let mut tweaks = self
+            .available_utxos(dbtx)
+            .await
+            .into_iter()
+            .map(|(_, utxo)| utxo.tweak)
+            .collect::<BTreeSet<[u8; 33]>>();
+
+        let unsigned_transactions = dbtx
+            .find_by_prefix(&UnsignedTransactionPrefixKey)
+            .await
+            .map(|(_, tx)| (tx.selected_utxos, None))
+            .collect::<Vec<_>>()
+            .await;
+
+        let pending_transactions = dbtx
+            .find_by_prefix(&PendingTransactionPrefixKey)
+            .await
+            .map(|(_, tx)| (tx.selected_utxos, Some(tx.tweak)))
+            .collect::<Vec<_>>()
+            .await;
+
+        for (selected_utxos, change_tweak) in unsigned_transactions
+            .into_iter()
+            .chain(pending_transactions)
+        {
+            tweaks.extend(selected_utxos.into_iter().map(|(_, utxo)| utxo.tweak));
+            tweaks.extend(change_tweak);
+        }
+
+        tweaks
+            .iter()
+            .map(|tweak| {
+                self.cfg
+                    .consensus
+                    .peg_in_descriptor
+                    .tweak(tweak, &self.secp)
+                    .to_string()
+            })
+            .collect()
+    }
+
     fn offline_wallet(&self) -> StatelessWallet {
         StatelessWallet {
             descriptor: &self.cfg.consensus.peg_in_descriptor,
@@ -1213,6 +1429,19 @@ impl Wallet {
         });
     }
 
+    fn spawn_peg_out_approval_cleanup_task(task_group: &mut TaskGroup, db: &Database) {
+        task_group.spawn_cancellable("wallet module: peg-out approval cleanup", {
+            let db = db.clone();
+            async move {
+                loop {
+                    sleep(PEG_OUT_APPROVAL_CLEANUP_INTERVAL).await;
+
+                    remove_stale_peg_out_approvals(&db).await;
+                }
+            }
+        });
+    }
+
     fn spawn_bitcoin_update_task(
         cfg: &WalletConfig,
         task_group: &mut TaskGroup,
@@ -1286,6 +1515,36 @@ impl Wallet {
     }
 }
 
+/// Removes the approvals of peg-outs that were fully signed in the meantime.
+///
+/// Approvals are written by the admin API, so consensus must not touch them as
+/// a write conflict with the API would be fatal for consensus. If our operator
+/// approves a peg-out concurrently we ignore the conflict and retry later.
+pub async fn remove_stale_peg_out_approvals(db: &Database) {
+    let mut dbtx = db.begin_transaction().await;
+
+    let approved_txids = dbtx
+        .find_by_prefix(&PegOutApprovalPrefix)
+        .await
+        .map(|(key, ())| key.0)
+        .collect::<Vec<_>>()
+        .await;
+
+    for txid in approved_txids {
+        if dbtx
+            .get_value(&UnsignedTransactionKey(txid))
+            .await
+            .is_none()
+        {
+            dbtx.remove_entry(&PegOutApprovalKey(txid)).await;
+        }
+    }
+
+    if let Err(e) = dbtx.commit_tx_result().await {
+        debug!(target: LOG_MODULE_WALLET, %e, "Failed to remove stale peg-out approvals");
+    }
+}
+
 #[instrument(level = "debug", skip_all)]
 pub async fn run_broadcast_pending_tx(db: Database, rpc: DynBitcoindRpc, tg_handle: &TaskHandle) {
     while !tg_handle.is_shutting_down() {
@@ -1325,6 +1584,103 @@ pub async fn broadcast_pending_tx(mut dbtx: DatabaseTransaction<'_>, rpc: &DynBi
     }
 }
 
+/// Finds the output of a peg-out transaction that pays the recipient instead
+/// of relying on the order [`StatelessWallet::create_tx`] arranges outputs in
+fn peg_out_txo(
+    tx: &Transaction,
+    destination: &ScriptBuf,
+    peg_out_amount: bitcoin::Amount,
+) -> Option<TxOutputSummary> {
+    let vout = tx.output.iter().position(|txo| {
+        txo.script_pubkey == *destination && txo.value == peg_out_amount.to_sat()
+    })?;
+
+    Some(TxOutputSummary {
+        outpoint: bitcoin::OutPoint {
+            txid: tx.txid(),
+            vout: vout as u32,
+        },
+        amount: peg_out_amount,
+    })
+}
+
+/// Verifies the signatures of `peer_key` for every input of the peg-out `psbt`
+/// and attaches them
+fn attach_peg_out_signature(
+    secp: &Secp256k1<All>,
+    peer_key: &CompressedPublicKey,
+    psbt: &mut PartiallySignedTransaction,
+    signature: &PegOutSignatureItem,
+) -> Result<(), ProcessPegOutSigError> {
+    if psbt.inputs.len() != signature.signature.len() {
+        return Err(ProcessPegOutSigError::WrongSignatureCount(
+            psbt.inputs.len(),
+            signature.signature.len(),
+        ));
+    }
+
+    let mut tx_hasher = SighashCache::new(&psbt.unsigned_tx);
+    for (idx, (input, signature)) in psbt
+        .inputs
+        .iter_mut()
+        .zip(signature.signature.iter())
+        .enumerate()
+    {
+        let tx_hash = tx_hasher
+            .segwit_signature_hash(
+                idx,
+                input
+                    .witness_script
+                    .as_ref()
+                    .expect("Missing witness script"),
+                input.witness_utxo.as_ref().expect("Missing UTXO").value,
+                EcdsaSighashType::All,
+            )
+            .map_err(|_| ProcessPegOutSigError::SighashError)?;
+
+        let tweak = input
+            .proprietary
+            .get(&proprietary_tweak_key())
+            .expect("we saved it with a tweak");
+
+        let tweaked_peer_key = peer_key.tweak(tweak, secp);
+        secp.verify_ecdsa(
+            &Message::from_slice(&tx_hash[..]).unwrap(),
+            signature,
+            &tweaked_peer_key.key,
+        )
+        .map_err(|_| ProcessPegOutSigError::InvalidSignature)?;
+
+        let signature = EcdsaSig::sighash_all(*signature);
+
+        if let Some(previous) = input
+            .partial_sigs
+            .insert(tweaked_peer_key.into(), signature)
+        {
+            // Peers only sign a PSBT once, so a retransmitted signature has to be
+            // identical to the one we already received
+            if previous != signature {
+                return Err(ProcessPegOutSigError::ConflictingSignature);
+            }
+
+            return Err(ProcessPegOutSigError::DuplicateSignature);
+        }
+    }
+    Ok(())
+}
+
+/// Counts signatures that can only stem from a misbehaving peer, while
+/// retransmissions of a signature we already received are ignored
+fn report_peg_out_signature_error(peer: PeerId, txid: Txid, error: &ProcessPegOutSigError) {
+    if error.is_peer_misbehavior() {
+        warn!(target: LOG_MODULE_WALLET, %peer, %txid, %error, "Peer sent a faulty peg-out signature");
+
+        WALLET_PEG_OUT_FAULTY_SIGNATURES_TOTAL
+            .with_label_values(&[&peer.to_string()])
+            .inc();
+    }
+}
+
 struct StatelessWallet<'a> {
     descriptor: &'a Descriptor<CompressedPublicKey>,
     secret_key: &'a secp256k1::SecretKey,
@@ -1734,15 +2090,24 @@ mod tests {
 
     use std::str::FromStr;
 
+    use bitcoin::psbt::PartiallySignedTransaction;
+    use bitcoin::sighash::{EcdsaSighashType, SighashCache};
     use bitcoin::Network::{Bitcoin, Testnet};
     use bitcoin::{Address, Amount, Network, OutPoint, Txid};
-    use fedimint_core::{BitcoinHash, Feerate};
-    use fedimint_wallet_common::{PegOut, PegOutFees, Rbf, WalletOutputV0};
+    use fedimint_core::{BitcoinHash, Feerate, PeerId};
+    use fedimint_wallet_common::tweakable::Tweakable;
+    use fedimint_wallet_common::{
+        proprietary_tweak_key, PegOut, PegOutFees, PegOutSignatureItem, ProcessPegOutSigError, Rbf,
+        WalletOutputV0,
+    };
     use miniscript::descriptor::Wsh;
+    use secp256k1::Message;
 
     use crate::common::PegInDescriptor;
+    use crate::metrics::WALLET_PEG_OUT_FAULTY_SIGNATURES_TOTAL;
     use crate::{
-        CompressedPublicKey, OsRng, SpendableUTXO, StatelessWallet, UTXOKey, WalletOutputError,
+        attach_peg_out_signature, report_peg_out_signature_error, CompressedPublicKey, OsRng,
+        SpendableUTXO, StatelessWallet, UTXOKey, WalletOutputError,
     };
 
     #[test]
@@ -1845,6 +2210,133 @@ mod tests {
         assert_eq!(res, Err(WalletOutputError::WrongNetwork(Testnet, Bitcoin)));
     }
 
+    #[test]
+    fn only_conflicting_peg_out_signatures_are_misbehavior() {
+        let secp = secp256k1::Secp256k1::new();
+        let (secret_key, public_key) = secp.generate_keypair(&mut OsRng);
+        let peer_key = CompressedPublicKey { key: public_key };
+
+        let descriptor = PegInDescriptor::Wsh(
+            Wsh::new_sortedmulti(
+                1,
+                vec![
+                    peer_key,
+                    CompressedPublicKey {
+                        key: secp.generate_keypair(&mut OsRng).1,
+                    },
+                ],
+            )
+            .unwrap(),
+        );
+
+        let wallet = StatelessWallet {
+            descriptor: &descriptor,
+            secret_key: &secret_key,
+            secp: &secp,
+        };
+
+        let recipient = Address::from_str("32iVBEu4dxkUQk9dJbZUiBiQdmypcEyJRf").unwrap();
+        let psbt = wallet
+            .create_tx(
+                Amount::from_sat(1000),
+                recipient.assume_checked().script_pubkey(),
+                vec![],
+                vec![(
+                    UTXOKey(OutPoint::null()),
+                    SpendableUTXO {
+                        tweak: [0; 33],
+                        amount: Amount::from_sat(3000),
+                    },
+                )],
+                Feerate { sats_per_kvb: 1000 },
+                &[0; 33],
+                None,
+            )
+            .expect("is ok")
+            .psbt;
+
+        let mut signed_psbt = psbt.clone();
+        wallet.sign_psbt(&mut signed_psbt);
+
+        let signature = PegOutSignatureItem {
+            txid: psbt.unsigned_tx.txid(),
+            signature: signed_psbt
+                .inputs
+                .iter()
+                .map(|input| input.partial_sigs.values().next().unwrap().sig)
+                .collect(),
+        };
+
+        let conflicting_signature = PegOutSignatureItem {
+            txid: psbt.unsigned_tx.txid(),
+            signature: vec![sign_with_nonce_data(&psbt, &secret_key, [1; 32])],
+        };
+
+        let mut received_psbt = psbt.clone();
+        attach_peg_out_signature(&secp, &peer_key, &mut received_psbt, &signature).unwrap();
+
+        let retransmission_error =
+            attach_peg_out_signature(&secp, &peer_key, &mut received_psbt.clone(), &signature)
+                .unwrap_err();
+        assert!(matches!(
+            retransmission_error,
+            ProcessPegOutSigError::DuplicateSignature
+        ));
+        assert!(!retransmission_error.is_peer_misbehavior());
+
+        let conflict_error =
+            attach_peg_out_signature(&secp, &peer_key, &mut received_psbt, &conflicting_signature)
+                .unwrap_err();
+        assert!(matches!(
+            conflict_error,
+            ProcessPegOutSigError::ConflictingSignature
+        ));
+        assert!(conflict_error.is_peer_misbehavior());
+
+        // Use a peer id no other test reports faulty signatures for
+        let peer = PeerId::from(u16::MAX);
+        let txid = psbt.unsigned_tx.txid();
+        let faulty_signatures = || {
+            WALLET_PEG_OUT_FAULTY_SIGNATURES_TOTAL
+                .with_label_values(&[&peer.to_string()])
+                .get()
+        };
+
+        report_peg_out_signature_error(peer, txid, &retransmission_error);
+        assert_eq!(faulty_signatures(), 0);
+
+        report_peg_out_signature_error(peer, txid, &conflict_error);
+        assert_eq!(faulty_signatures(), 1);
+    }
+
+    /// Creates a valid signature for the only input of `psbt` that differs from
+    /// the deterministic one created by [`StatelessWallet::sign_psbt`]
+    fn sign_with_nonce_data(
+        psbt: &PartiallySignedTransaction,
+        secret_key: &secp256k1::SecretKey,
+        nonce_data: [u8; 32],
+    ) -> secp256k1::ecdsa::Signature {
+        let secp = secp256k1::Secp256k1::new();
+        let input = &psbt.inputs[0];
+
+        let tx_hash = SighashCache::new(&psbt.unsigned_tx)
+            .segwit_signature_hash(
+                0,
+                input.witness_script.as_ref().unwrap(),
+                input.witness_utxo.as_ref().unwrap().value,
+                EcdsaSighashType::All,
+            )
+            .unwrap();
+
+        let tweak = input.proprietary.get(&proprietary_tweak_key()).unwrap();
+
+        secp.sign_ecdsa_with_noncedata(
+            &Message::from_slice(&tx_hash[..]).unwrap(),
+            &secret_key.tweak(tweak, &secp),
+            &nonce_data,
+        )
+    }
+
     fn rbf(sats_per_kvb: u64, total_weight: u64) -> WalletOutputV0 {
         WalletOutputV0::Rbf(Rbf {
             fees: PegOutFees::new(sats_per_kvb, total_weight),
@@ -2,8 +2,8 @@ use fedimint_metrics::prometheus::{
     register_histogram_vec_with_registry, register_int_gauge_with_registry, IntGauge,
 };
 use fedimint_metrics::{
-    histogram_opts, opts, register_histogram_with_registry, Histogram, HistogramVec,
-    AMOUNTS_BUCKETS_SATS, REGISTRY,
+    histogram_opts, opts, register_histogram_with_registry, register_int_counter_vec_with_registry,
+    Histogram, HistogramVec, IntCounterVec, AMOUNTS_BUCKETS_SATS, REGISTRY,
 };
 use once_cell::sync::Lazy;
 
@@ -85,3 +85,14 @@ pub(crate) static WALLET_BLOCK_COUNT: Lazy<IntGauge> = Lazy::new(|| {
     )
     .unwrap()
 });
+pub(crate) static WALLET_PEG_OUT_FAULTY_SIGNATURES_TOTAL: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec_with_registry!(
+        opts!(
+            "wallet_peg_out_faulty_signatures_total",
+            "Number of invalid or conflicting peg-out signatures received by peer",
+        ),
+        &["peer_id"],
+        REGISTRY
+    )
+    .unwrap()
+});
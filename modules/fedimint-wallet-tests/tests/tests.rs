@@ -1,3 +1,4 @@
+use std::str::FromStr;
 use std::time::Duration;
 
 use anyhow::{bail, Context};
@@ -9,6 +10,7 @@ use fedimint_core::bitcoin_migration::checked_address_to_unchecked_address;
 use fedimint_core::db::mem_impl::MemDatabase;
 use fedimint_core::db::{DatabaseTransaction, IRawDatabaseExt};
 use fedimint_core::envs::BitcoinRpcConfig;
+use fedimint_core::module::ApiAuth;
 use fedimint_core::task::sleep_in_test;
 use fedimint_core::util::{BoxStream, NextOrPending};
 use fedimint_core::{sats, time, Amount, Feerate, PeerId, ServerModule};
@@ -22,16 +24,20 @@ use fedimint_wallet_client::{DepositState, WalletClientInit, WalletClientModule,
 use fedimint_wallet_common::config::{WalletConfig, WalletGenParams};
 use fedimint_wallet_common::tweakable::Tweakable;
 use fedimint_wallet_common::txoproof::PegInProof;
-use fedimint_wallet_common::{PegOutFees, Rbf};
+use fedimint_wallet_common::{PegInDescriptor, PegOutFees, Rbf, TxOutputSummary};
 use fedimint_wallet_server::WalletInit;
 use futures::stream::StreamExt;
 use tracing::info;
 
 fn fixtures() -> Fixtures {
+    fixtures_with_wallet(WalletInit::default())
+}
+
+fn fixtures_with_wallet(wallet_server: WalletInit) -> Fixtures {
     let fixtures = Fixtures::new_primary(DummyClientInit, DummyInit, DummyGenParams::default());
     let wallet_params = WalletGenParams::regtest(fixtures.bitcoin_server());
     let wallet_client = WalletClientInit::new(fixtures.bitcoin_client());
-    fixtures.with_module(wallet_client, WalletInit, wallet_params)
+    fixtures.with_module(wallet_client, wallet_server, wallet_params)
 }
 
 fn bsats(satoshi: u64) -> bitcoin::Amount {
@@ -338,6 +344,262 @@ async fn peg_outs_support_rbf_with_env_var_enabled() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn peg_outs_above_threshold_require_approval() -> anyhow::Result<()> {
+    let peg_out_approval_threshold = 2 * PEG_OUT_AMOUNT_SATS;
+    let fixtures = fixtures_with_wallet(WalletInit::with_peg_out_approval_threshold(bsats(
+        peg_out_approval_threshold,
+    )));
+    let fed = fixtures.new_default_fed().await;
+    let client = fed.new_client().await;
+    let bitcoin = fixtures.bitcoin();
+    // Need lock to keep tx in mempool from getting mined
+    let bitcoin = bitcoin.lock_exclusive().await;
+    info!("Starting test peg_outs_above_threshold_require_approval");
+
+    let finality_delay = 10;
+    bitcoin.mine_blocks(finality_delay).await;
+    await_consensus_to_catch_up(&client, 1).await?;
+
+    peg_in(&client, bitcoin.as_ref(), finality_delay).await?;
+
+    info!("Peg-in finished for test peg_outs_above_threshold_require_approval");
+    let address = checked_address_to_unchecked_address(&bitcoin.get_new_address().await);
+    let peg_out = bsats(peg_out_approval_threshold + PEG_OUT_AMOUNT_SATS);
+    let wallet_module = client.get_first_module::<WalletClientModule>();
+    let fees = wallet_module
+        .get_withdraw_fees(address.clone(), peg_out)
+        .await?;
+    let op = wallet_module
+        .withdraw(address.clone(), peg_out, fees, ())
+        .await?;
+
+    let sub = wallet_module.subscribe_withdraw_updates(op).await?;
+    let mut sub = sub.into_stream();
+    assert_eq!(sub.ok().await?, WithdrawState::Created);
+    let state = sub.ok().await?;
+    let WithdrawState::Succeeded(txid) = state else {
+        bail!("Unexpected state: {state:?}")
+    };
+
+    let auth = ApiAuth("pass".to_string());
+    // The default test federation has its last peer offline
+    let mut admin_clients = Vec::new();
+    for peer_id in 0..3 {
+        admin_clients.push(
+            fed.new_admin_client(PeerId::from(peer_id), auth.clone())
+                .await,
+        );
+    }
+    let expected_pending = vec![TxOutputSummary {
+        outpoint: bitcoin::OutPoint { txid, vout: 0 },
+        amount: peg_out,
+    }];
+
+    // Give the guardians a few sessions to exchange signatures, which they must
+    // withhold until the peg-out is approved. A peg-out is only listed as long as
+    // it is not fully signed.
+    sleep_in_test(
+        "waiting for peg-out signatures to be withheld",
+        Duration::from_secs(5),
+    )
+    .await;
+
+    for admin_client in &admin_clients {
+        let wallet_api = admin_client.api().with_module(wallet_module.id);
+
+        assert_eq!(
+            wallet_api
+                .fetch_pending_peg_out_approvals(auth.clone())
+                .await?,
+            expected_pending
+        );
+    }
+
+    for admin_client in &admin_clients {
+        let wallet_api = admin_client.api().with_module(wallet_module.id);
+
+        assert!(wallet_api.approve_peg_out(auth.clone(), txid).await?);
+        assert!(wallet_api
+            .fetch_pending_peg_out_approvals(auth.clone())
+            .await?
+            .is_empty());
+    }
+
+    assert_eq!(
+        bitcoin.get_mempool_tx_fee(&txid).await,
+        fees.amount().into()
+    );
+    assert_eq!(
+        bitcoin
+            .mine_block_and_get_received(&address.assume_checked())
+            .await,
+        sats(peg_out.to_sat())
+    );
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn wallet_summary_tracks_pending_peg_outs() -> anyhow::Result<()> {
+    // The approval threshold keeps the peg-out unsigned until we approve it
+    let fixtures = fixtures_with_wallet(WalletInit::with_peg_out_approval_threshold(bsats(0)));
+    let fed = fixtures.new_default_fed().await;
+    let client = fed.new_client().await;
+    let bitcoin = fixtures.bitcoin();
+    // Need lock to keep tx in mempool from getting mined
+    let bitcoin = bitcoin.lock_exclusive().await;
+    info!("Starting test wallet_summary_tracks_pending_peg_outs");
+
+    let finality_delay = 10;
+    bitcoin.mine_blocks(finality_delay).await;
+    await_consensus_to_catch_up(&client, 1).await?;
+
+    peg_in(&client, bitcoin.as_ref(), finality_delay).await?;
+
+    info!("Peg-in finished for test wallet_summary_tracks_pending_peg_outs");
+    let address = checked_address_to_unchecked_address(&bitcoin.get_new_address().await);
+    let peg_out = bsats(PEG_OUT_AMOUNT_SATS);
+    let wallet_module = client.get_first_module::<WalletClientModule>();
+    let fees = wallet_module
+        .get_withdraw_fees(address.clone(), peg_out)
+        .await?;
+    let op = wallet_module.withdraw(address, peg_out, fees, ()).await?;
+
+    let sub = wallet_module.subscribe_withdraw_updates(op).await?;
+    let mut sub = sub.into_stream();
+    assert_eq!(sub.ok().await?, WithdrawState::Created);
+    let state = sub.ok().await?;
+    let WithdrawState::Succeeded(txid) = state else {
+        bail!("Unexpected state: {state:?}")
+    };
+
+    let wallet_api = client.api().with_module(wallet_module.id);
+    let peg_out_txo = TxOutputSummary {
+        outpoint: bitcoin::OutPoint { txid, vout: 0 },
+        amount: peg_out,
+    };
+
+    let summary = wallet_api.fetch_wallet_summary().await?;
+    assert_eq!(summary.unsigned_peg_out_txos, vec![peg_out_txo.clone()]);
+    assert_eq!(summary.total_unsigned_peg_out_balance(), peg_out);
+    assert_eq!(
+        summary.total_unconfirmed_peg_out_balance(),
+        bitcoin::Amount::ZERO
+    );
+
+    let auth = ApiAuth("pass".to_string());
+    // The default test federation has its last peer offline
+    for peer_id in 0..3 {
+        let admin_client = fed
+            .new_admin_client(PeerId::from(peer_id), auth.clone())
+            .await;
+
+        assert!(
+            admin_client
+                .api()
+                .with_module(wallet_module.id)
+                .approve_peg_out(auth.clone(), txid)
+                .await?
+        );
+    }
+
+    // Waits for the fully signed peg-out to be broadcast
+    bitcoin.get_mempool_tx_fee(&txid).await;
+
+    let summary = wallet_api.fetch_wallet_summary().await?;
+    assert_eq!(summary.unconfirmed_peg_out_txos, vec![peg_out_txo]);
+    assert_eq!(summary.total_unconfirmed_peg_out_balance(), peg_out);
+    assert_eq!(
+        summary.total_unsigned_peg_out_balance(),
+        bitcoin::Amount::ZERO
+    );
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn watch_only_descriptors_match_federation_scripts() -> anyhow::Result<()> {
+    let fixtures = fixtures();
+    let fed = fixtures.new_default_fed().await;
+    let client = fed.new_client().await;
+    let bitcoin = fixtures.bitcoin();
+    // Need lock to keep tx in mempool from getting mined
+    let bitcoin = bitcoin.lock_exclusive().await;
+    info!("Starting test watch_only_descriptors_match_federation_scripts");
+
+    let finality_delay = 10;
+    bitcoin.mine_blocks(finality_delay).await;
+    await_consensus_to_catch_up(&client, 1).await?;
+
+    let wallet_module = client.get_first_module::<WalletClientModule>();
+    let valid_until = time::now() + PEG_IN_TIMEOUT;
+    let (op, peg_in_address) = wallet_module.get_deposit_address(valid_until, ()).await?;
+    let (_proof, peg_in_tx) = bitcoin
+        .send_and_mine_block(
+            &peg_in_address,
+            bsats(PEG_IN_AMOUNT_SATS)
+                + bsats(wallet_module.get_fee_consensus().peg_in_abs.msats / 1000),
+        )
+        .await;
+    bitcoin.mine_blocks(finality_delay).await;
+
+    let mut sub = wallet_module
+        .subscribe_deposit_updates(op)
+        .await?
+        .into_stream();
+    while !matches!(sub.ok().await?, DepositState::Claimed(_)) {}
+
+    info!("Peg-in finished for test watch_only_descriptors_match_federation_scripts");
+    let peg_in_script = peg_in_address.script_pubkey();
+    let peg_in_vout = peg_in_tx
+        .output
+        .iter()
+        .position(|txo| txo.script_pubkey == peg_in_script)
+        .context("peg-in output not found")?;
+
+    let summary = client
+        .api()
+        .with_module(wallet_module.id)
+        .fetch_wallet_summary()
+        .await?;
+    assert_eq!(
+        summary
+            .spendable_utxos
+            .iter()
+            .map(|utxo| utxo.outpoint)
+            .collect::<Vec<_>>(),
+        vec![bitcoin::OutPoint {
+            txid: peg_in_tx.txid(),
+            vout: peg_in_vout as u32,
+        }]
+    );
+
+    let auth = ApiAuth("pass".to_string());
+    // The default test federation has its last peer offline
+    for peer_id in 0..3 {
+        let admin_client = fed
+            .new_admin_client(PeerId::from(peer_id), auth.clone())
+            .await;
+
+        let scripts = admin_client
+            .api()
+            .with_module(wallet_module.id)
+            .fetch_watch_only_descriptors(auth.clone())
+            .await?
+            .iter()
+            .map(|descriptor| {
+                PegInDescriptor::from_str(descriptor).map(|descriptor| descriptor.script_pubkey())
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // The only UTXO of the federation is the one created by our peg-in
+        assert_eq!(scripts, vec![peg_in_script.clone()]);
+    }
+
+    Ok(())
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn rbf_withdrawals_are_rejected() -> anyhow::Result<()> {
     let fixtures = fixtures();
@@ -533,6 +795,7 @@ async fn peg_ins_that_are_unconfirmed_are_rejected() -> anyhow::Result<()> {
         dyn_bitcoin_rpc.clone(),
         &mut task_group,
         PeerId::from(0),
+        None,
     )
     .await?;
 
@@ -647,7 +910,7 @@ fn build_wallet_server_configs(
 )> {
     let peers = (0..MINTS as u16).map(PeerId::from).collect::<Vec<_>>();
     let wallet_cfg = fedimint_core::module::ServerModuleInit::trusted_dealer_gen(
-        &WalletInit,
+        &WalletInit::default(),
         &peers,
         &fedimint_core::config::ConfigGenModuleParams::from_typed(WalletGenParams {
             local: fedimint_wallet_common::config::WalletGenParamsLocal {
@@ -666,7 +929,7 @@ fn build_wallet_server_configs(
         <WalletInit as fedimint_core::module::ServerModuleInit>::kind(),
         fedimint_core::module::ModuleConsensusVersion::new(0, 0),
         fedimint_core::module::ServerModuleInit::get_client_config(
-            &WalletInit,
+            &WalletInit::default(),
             &wallet_cfg[&PeerId::from(0)].consensus,
         )?,
     )?;
@@ -901,7 +1164,7 @@ mod fedimint_migration_tests {
     async fn test_server_db_migrations() -> anyhow::Result<()> {
         let _ = TracingSetup::default().init();
 
-        let module = DynServerModuleInit::from(WalletInit);
+        let module = DynServerModuleInit::from(WalletInit::default());
         validate_migrations_server(
             module,
             "wallet-server",
@@ -1021,6 +1284,9 @@ mod fedimint_migration_tests {
                             );
                             info!("Validated FeeRateVote");
                         }
+                        // Peg-out approvals were introduced after the v0 snapshot was taken and
+                        // are only written for peg-outs above a locally configured threshold
+                        DbKeyPrefix::PegOutApproval => {}
                     }
                 }
                 Ok(())